@@ -0,0 +1,481 @@
+use std::borrow::Cow;
+
+use crate::{JsonNavError, Navigable};
+
+/// The conversion `json_nav!` dispatches `; as <type>` to for values of type `V`
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use serde_json::{json, Value};
+/// use json_nav::{json_nav, FromJsonNav, JsonNavError};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Email<'a>(&'a str);
+///
+/// impl<'a> FromJsonNav<'a, Value> for Email<'a> {
+///     fn from_json_nav(value: &'a Value) -> Result<Self, JsonNavError> {
+///         value.as_str()
+///             .filter(|email| email.contains('@'))
+///             .map(Email)
+///             .ok_or(JsonNavError::TypeMismatch { expected: "email address" })
+///     }
+/// }
+///
+/// let value = json!({ "user": { "email": "jane@example.com", "name": "Jane" } });
+///
+/// assert_eq!(Ok(Email("jane@example.com")), json_nav! { value => "user" => "email"; as Email });
+///
+/// let error = json_nav! { value => "user" => "name"; as Email };
+/// assert_eq!(Err(JsonNavError::TypeMismatch { expected: "email address" }), error);
+/// # }
+/// ```
+pub trait FromJsonNav<'a, V>: Sized {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError>;
+
+    /// The conversion `; as <type> with <policy>` dispatches to, only the built in scalar
+    /// conversions look at the policy, everything else converts like `from_json_nav`
+    fn from_json_nav_with(value: &'a V, _policy: &Policy) -> Result<Self, JsonNavError> {
+        Self::from_json_nav(value)
+    }
+}
+
+/// How `; as <type>` conversions treat values that are not quite of the type they convert to,
+/// `; as <type> with <policy>` applies one to a single conversion
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use std::borrow::Cow;
+/// use serde_json::json;
+/// use json_nav::{json_nav, JsonNavError, Policy};
+///
+/// const VENDOR: Policy = Policy::LENIENT;
+///
+/// let order = json!({ "id": 1017, "quantity": "42", "gift": "true", "sku": "A-1" });
+///
+/// assert_eq!(Ok(42), json_nav! { order => "quantity"; as u64 with VENDOR });
+/// assert_eq!(Ok(true), json_nav! { order => "gift"; as bool lenient });
+/// assert_eq!(Ok(Cow::Owned("1017".to_owned())), json_nav! { order => "id"; as str lenient });
+///
+/// let error = json_nav! { order => "sku"; as u64 lenient };
+/// assert_eq!(Err(JsonNavError::Parse { input: "A-1".to_owned(), target: "u64" }), error);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Policy {
+    lenient: bool,
+    floats: FloatConversion,
+    overflow: Overflow,
+    finite: bool,
+}
+
+impl Policy {
+    /// Only accepts values of the type that is converted to, the policy of a plain `; as <type>`
+    pub const STRICT: Policy = Policy {
+        lenient: false,
+        floats: FloatConversion::Reject,
+        overflow: Overflow::Error,
+        finite: false,
+    };
+
+    /// Also parses numbers and booleans from strings, and writes numbers and booleans
+    /// for `; as str`, the policy of `; as <type> lenient`
+    pub const LENIENT: Policy = Policy::STRICT.lenient(true);
+
+    pub const fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// How integer conversions treat floats
+    pub const fn floats(mut self, floats: FloatConversion) -> Self {
+        self.floats = floats;
+        self
+    }
+
+    /// How integer conversions treat numbers outside of the target's range
+    pub const fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Whether `; as f64` rejects NaN and infinities
+    pub const fn finite(mut self, finite: bool) -> Self {
+        self.finite = finite;
+        self
+    }
+
+    pub const fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+}
+
+/// How integer conversions treat floats, NaN and infinities are never converted
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use serde_json::json;
+/// use json_nav::{json_nav, FloatConversion, JsonNavError, Overflow, Policy};
+///
+/// let value = json!({ "count": 1e3, "ratio": 2.5, "huge": 1e30 });
+///
+/// const INTEGRAL: Policy = Policy::STRICT.floats(FloatConversion::Integral);
+/// assert_eq!(Ok(1000), json_nav! { value => "count"; as u64 with INTEGRAL });
+/// assert_eq!(Ok(3), json_nav! { value => "ratio"; as u8 with INTEGRAL.floats(FloatConversion::Round) });
+/// assert_eq!(Ok(2), json_nav! { value => "ratio"; as u8 with INTEGRAL.floats(FloatConversion::Truncate) });
+/// assert_eq!(Ok(u32::MAX), json_nav! { value => "huge"; as u32 with INTEGRAL.overflow(Overflow::Saturate) });
+///
+/// let error = json_nav! { value => "ratio"; as u64 with INTEGRAL };
+/// assert_eq!("2.5 cannot be converted to u64, it is not an integer", error.unwrap_err().to_string());
+///
+/// let error = json_nav! { value => "huge"; as u64 with INTEGRAL };
+/// assert_eq!("1e+30 is out of range for u64, expected 0..=18446744073709551615", error.unwrap_err().to_string());
+///
+/// let error = json_nav! { value => "count"; as u64 };
+/// assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), error);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FloatConversion {
+    /// Floats are not integers, even if they have no fractional part
+    #[default]
+    Reject,
+    /// Floats without a fractional part are converted
+    Integral,
+    /// Floats are rounded to the nearest integer, away from zero at halves
+    Round,
+    /// Floats are rounded towards zero
+    Truncate,
+}
+
+/// How integer conversions treat numbers outside of the target's range
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overflow {
+    #[default]
+    Error,
+    /// Numbers are clamped to the target's minimum or maximum
+    Saturate,
+}
+
+impl<'a, V> FromJsonNav<'a, V> for &'a V {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        Ok(value)
+    }
+}
+
+// `; as object` and `; as array` go through `Navigable` so they also work on roots that aren't values,
+// these are for naming the types directly and for code generic over `FromJsonNav`
+macro_rules! impl_collections_from_json_nav {
+    ($($feature:literal $value:ty => $object:ty, $array:ty);* $(;)?) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl<'a> FromJsonNav<'a, $value> for &'a $object {
+                fn from_json_nav(value: &'a $value) -> Result<Self, JsonNavError> {
+                    Navigable::as_object(value).ok_or(JsonNavError::TypeMismatch { expected: "object" })
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl<'a> FromJsonNav<'a, $value> for &'a $array {
+                fn from_json_nav(value: &'a $value) -> Result<Self, JsonNavError> {
+                    Navigable::as_array(value).ok_or(JsonNavError::TypeMismatch { expected: "array" })
+                }
+            }
+        )*
+    };
+}
+
+impl_collections_from_json_nav! {
+    "serde_json" serde_json::Value => serde_json::Map<String, serde_json::Value>, Vec<serde_json::Value>;
+    "toml" toml::Value => toml::Table, toml::value::Array;
+    "serde_yaml" serde_yaml::Value => serde_yaml::Mapping, serde_yaml::Sequence;
+    "ciborium" ciborium::Value => Vec<(ciborium::Value, ciborium::Value)>, Vec<ciborium::Value>;
+    "rmpv" rmpv::Value => Vec<(rmpv::Value, rmpv::Value)>, Vec<rmpv::Value>;
+}
+
+impl<'a, V: Navigable> FromJsonNav<'a, V> for &'a str {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        value.as_str().ok_or(JsonNavError::TypeMismatch { expected: "str" })
+    }
+}
+
+impl<'a, V: Navigable> FromJsonNav<'a, V> for Cow<'a, str> {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        Self::from_json_nav_with(value, &Policy::STRICT)
+    }
+
+    fn from_json_nav_with(value: &'a V, policy: &Policy) -> Result<Self, JsonNavError> {
+        if let Some(s) = value.as_str() {
+            return Ok(Cow::Borrowed(s));
+        }
+
+        let written = match policy.lenient {
+            true => value.as_bool().map(|b| Cow::Owned(b.to_string())).or_else(|| value.as_number_str()),
+            false => None,
+        };
+
+        written.ok_or(JsonNavError::TypeMismatch { expected: "str" })
+    }
+}
+
+impl<'a, V: Navigable> FromJsonNav<'a, V> for bool {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        Self::from_json_nav_with(value, &Policy::STRICT)
+    }
+
+    fn from_json_nav_with(value: &'a V, policy: &Policy) -> Result<Self, JsonNavError> {
+        match (value.as_bool(), value.as_str()) {
+            (Some(b), _) => Ok(b),
+            (None, Some(s)) if policy.lenient => parse(s, "bool"),
+            _ => Err(JsonNavError::TypeMismatch { expected: "bool" }),
+        }
+    }
+}
+
+impl<'a, V: Navigable> FromJsonNav<'a, V> for f64 {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        Self::from_json_nav_with(value, &Policy::STRICT)
+    }
+
+    fn from_json_nav_with(value: &'a V, policy: &Policy) -> Result<Self, JsonNavError> {
+        let (n, written) = match (value.as_f64(), value.as_str()) {
+            (Some(n), _) => (n, written(value, n)),
+            (None, Some(s)) if policy.lenient => (parse(s, "f64")?, Cow::Borrowed(s.trim())),
+            _ => return Err(JsonNavError::TypeMismatch { expected: "f64" }),
+        };
+
+        match policy.finite && !n.is_finite() {
+            true => Err(invalid_number(&written, "f64", "it is not finite")),
+            false => Ok(n),
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl<'a> FromJsonNav<'a, toml::Value> for &'a toml::value::Datetime {
+    fn from_json_nav(value: &'a toml::Value) -> Result<Self, JsonNavError> {
+        value.as_datetime().ok_or(JsonNavError::TypeMismatch { expected: "datetime" })
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl<'a> FromJsonNav<'a, serde_yaml::Value> for &'a serde_yaml::value::TaggedValue {
+    fn from_json_nav(value: &'a serde_yaml::Value) -> Result<Self, JsonNavError> {
+        match value {
+            serde_yaml::Value::Tagged(tagged) => Ok(tagged),
+            _ => Err(JsonNavError::TypeMismatch { expected: "tagged value" }),
+        }
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl<'a> FromJsonNav<'a, ciborium::Value> for (u64, &'a ciborium::Value) {
+    fn from_json_nav(value: &'a ciborium::Value) -> Result<Self, JsonNavError> {
+        value.as_tag().ok_or(JsonNavError::TypeMismatch { expected: "tagged value" })
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl<'a> FromJsonNav<'a, ciborium::Value> for &'a [u8] {
+    fn from_json_nav(value: &'a ciborium::Value) -> Result<Self, JsonNavError> {
+        match crate::navigable::untag_cbor(value) {
+            ciborium::Value::Bytes(bytes) => Ok(bytes),
+            _ => Err(JsonNavError::TypeMismatch { expected: "bytes" }),
+        }
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl<'a> FromJsonNav<'a, rmpv::Value> for (i8, &'a [u8]) {
+    fn from_json_nav(value: &'a rmpv::Value) -> Result<Self, JsonNavError> {
+        value.as_ext().ok_or(JsonNavError::TypeMismatch { expected: "ext value" })
+    }
+}
+
+// strings are not bytes here, even though msgpack stores them the same way
+#[cfg(feature = "rmpv")]
+impl<'a> FromJsonNav<'a, rmpv::Value> for &'a [u8] {
+    fn from_json_nav(value: &'a rmpv::Value) -> Result<Self, JsonNavError> {
+        match value {
+            rmpv::Value::Binary(bytes) => Ok(bytes),
+            _ => Err(JsonNavError::TypeMismatch { expected: "bytes" }),
+        }
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl<'a, V: Navigable> FromJsonNav<'a, V> for rust_decimal::Decimal {
+    fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+        Self::from_json_nav_with(value, &Policy::STRICT)
+    }
+
+    fn from_json_nav_with(value: &'a V, policy: &Policy) -> Result<Self, JsonNavError> {
+        use rust_decimal::{Decimal, Error};
+
+        let (digits, number) = match (value.as_number_str(), value.as_str()) {
+            (Some(digits), _) => (digits, true),
+            (None, Some(s)) if policy.lenient => (Cow::Borrowed(s.trim()), false),
+            _ => return Err(JsonNavError::TypeMismatch { expected: "decimal" }),
+        };
+
+        let decimal = match digits.contains(['e', 'E']) {
+            true => Decimal::from_scientific(&digits),
+            false => Decimal::from_str_exact(&digits),
+        };
+
+        let reason = match decimal {
+            Ok(decimal) => return Ok(decimal),
+            Err(Error::Underflow | Error::ScaleExceedsMaximumPrecision(_)) => "it would lose precision",
+            Err(Error::ExceedsMaximumPossibleValue | Error::LessThanMinimumPossibleValue) => {
+                // the mantissa of a decimal has 96 bits
+                let max = (1u128 << 96) - 1;
+                return Err(JsonNavError::OutOfRange { value: digits.into_owned(), target: "decimal", min: -(max as i128), max });
+            },
+            Err(_) if number => "it is not a decimal",
+            Err(_) => return Err(JsonNavError::Parse { input: digits.into_owned(), target: "decimal" }),
+        };
+
+        Err(JsonNavError::InvalidNumber { value: digits.into_owned(), target: "decimal", reason })
+    }
+}
+
+/// Converts a number to `T`, reporting a value outside of `min..=max` as out of range
+/// unless the policy saturates
+fn to_integer<T: TryFrom<i128> + TryFrom<u128>, V: Navigable>(
+    value: &V,
+    policy: &Policy,
+    target: &'static str,
+    min: i128,
+    max: u128,
+) -> Result<T, JsonNavError> {
+    let max_integer = i128::try_from(max).unwrap_or(i128::MAX);
+
+    let number = match to_number(value, policy, target)? {
+        Number::Float(float, written) => float_to_integer(float, &written, policy, target, min, max)?,
+        number => number,
+    };
+
+    let value = match number {
+        Number::Integer(integer) => integer,
+        Number::Float(..) => unreachable!("floats were just converted to integers"),
+        // only fits a `u128`, and saturates to the maximum of anything else
+        Number::Unsigned(unsigned) => {
+            let unsigned = match policy.overflow {
+                Overflow::Saturate => unsigned.min(max),
+                Overflow::Error => unsigned,
+            };
+
+            return T::try_from(unsigned)
+                .map_err(|_| JsonNavError::OutOfRange { value: unsigned.to_string(), target, min, max });
+        },
+    };
+
+    let value = match policy.overflow {
+        Overflow::Saturate => value.clamp(min, max_integer),
+        Overflow::Error => value,
+    };
+
+    T::try_from(value).map_err(|_| JsonNavError::OutOfRange { value: value.to_string(), target, min, max })
+}
+
+enum Number<'a> {
+    Integer(i128),
+    /// An integer above `i128::MAX`
+    Unsigned(u128),
+    /// A float and how it was written, for errors
+    Float(f64, Cow<'a, str>),
+}
+
+/// Reads an integer written as digits in a string
+fn parse_integer(digits: &str) -> Option<Number<'static>> {
+    digits.parse().map(Number::Integer)
+        .or_else(|_| digits.parse().map(Number::Unsigned))
+        .ok()
+}
+
+/// The number `value` holds, floats are only considered if the policy converts them
+fn to_number<'a, V: Navigable>(value: &'a V, policy: &Policy, target: &'static str) -> Result<Number<'a>, JsonNavError> {
+    let floats = policy.floats != FloatConversion::Reject;
+
+    if let Some(integer) = value.as_i128() {
+        return Ok(Number::Integer(integer));
+    }
+
+    if let Some(unsigned) = value.as_u128() {
+        return Ok(Number::Unsigned(unsigned));
+    }
+
+    match (value.as_f64(), value.as_str()) {
+        (Some(float), _) if floats => Ok(Number::Float(float, written(value, float))),
+        (None, Some(s)) if policy.lenient => match parse_integer(s.trim()) {
+            Some(number) => Ok(number),
+            None if floats => parse(s, target).map(|float| Number::Float(float, Cow::Borrowed(s.trim()))),
+            None => Err(JsonNavError::Parse { input: s.to_owned(), target }),
+        },
+        _ => Err(JsonNavError::TypeMismatch { expected: target }),
+    }
+}
+
+/// Converts a float to an integer or unsigned number following the policy,
+/// the result may still be out of range if it saturates
+fn float_to_integer(
+    float: f64,
+    written: &str,
+    policy: &Policy,
+    target: &'static str,
+    min: i128,
+    max: u128,
+) -> Result<Number<'static>, JsonNavError> {
+    if !float.is_finite() {
+        return Err(invalid_number(written, target, "it is not finite"));
+    }
+
+    let integer = match policy.floats {
+        FloatConversion::Reject => return Err(JsonNavError::TypeMismatch { expected: target }),
+        FloatConversion::Integral if float.fract() != 0.0 => return Err(invalid_number(written, target, "it is not an integer")),
+        FloatConversion::Integral => float,
+        FloatConversion::Round => float.round(),
+        FloatConversion::Truncate => float.trunc(),
+    };
+
+    // `max + 1` is a power of two, so it survives the conversion to `f64` unlike `max` itself
+    let in_range = integer >= min as f64 && integer < max as f64 + 1.0;
+
+    match (in_range, policy.overflow) {
+        (false, Overflow::Error) => Err(JsonNavError::OutOfRange { value: written.to_owned(), target, min, max }),
+        // `i128::MAX` rounds up to `i128::MAX + 1` as a float, anything from there on only fits a `u128`
+        _ if integer >= i128::MAX as f64 => Ok(Number::Unsigned(integer as u128)),
+        _ => Ok(Number::Integer(integer as i128)),
+    }
+}
+
+fn invalid_number(written: &str, target: &'static str, reason: &'static str) -> JsonNavError {
+    JsonNavError::InvalidNumber { value: written.to_owned(), target, reason }
+}
+
+/// The digits of a float as the format keeps them, infinities and NaN have none
+fn written<V: Navigable>(value: &V, float: f64) -> Cow<'_, str> {
+    value.as_number_str().unwrap_or_else(|| Cow::Owned(format!("{float:?}")))
+}
+
+/// Parses a string that stands in for a `target`, surrounding whitespace is ignored
+fn parse<T: std::str::FromStr>(s: &str, target: &'static str) -> Result<T, JsonNavError> {
+    s.trim().parse().map_err(|_| JsonNavError::Parse { input: s.to_owned(), target })
+}
+
+macro_rules! impl_integer_from_json_nav {
+    ($($int:ident)*) => {
+        $(
+            impl<'a, V: Navigable> FromJsonNav<'a, V> for $int {
+                fn from_json_nav(value: &'a V) -> Result<Self, JsonNavError> {
+                    Self::from_json_nav_with(value, &Policy::STRICT)
+                }
+
+                fn from_json_nav_with(value: &'a V, policy: &Policy) -> Result<Self, JsonNavError> {
+                    to_integer(value, policy, stringify!($int), $int::MIN as i128, $int::MAX as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_from_json_nav!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
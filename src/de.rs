@@ -0,0 +1,223 @@
+//! A serde deserializer over a [`Cursor`] that remembers the path of the value it failed on
+
+use std::fmt;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+
+use crate::internal::Cursor;
+use crate::{JsonNavError, NavPath, PathSegment};
+
+/// Deserializes the value under `x`, borrowing from it where `T` allows
+pub fn deserialize<'a, T: de::Deserialize<'a>>(x: Cursor<'a, Value>) -> Result<T, JsonNavError> {
+    let path = x.path().clone();
+
+    T::deserialize(x).map_err(|e| {
+        let field = e.field.map_or_else(Vec::new, |field| field.segments()[path.segments().len()..].to_vec());
+        JsonNavError::Deserialize { path, field, message: e.message }
+    })
+}
+
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+    field: Option<NavPath>,
+}
+
+impl Error {
+    /// Attributes this error to `path`, unless a more deeply nested value already failed
+    fn at(mut self, path: &NavPath) -> Self {
+        self.field.get_or_insert_with(|| path.clone());
+        self
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error { message: message.to_string(), field: None }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<'de> Deserializer<'de> for Cursor<'de, Value> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let path = self.path().clone();
+
+        match self.value().map_err(de::Error::custom)? {
+            Value::Array(_) => visit_seq(self, visitor),
+            Value::Object(_) => visit_map(self, visitor),
+            value => value.deserialize_any(visitor).map_err(de::Error::custom),
+        }.map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value().map_err(de::Error::custom)? {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let path = self.path().clone();
+
+        match self.value().map_err(de::Error::custom)? {
+            Value::String(variant) => visitor.visit_enum(BorrowedStrDeserializer::new(variant)),
+            Value::Object(object) if object.len() == 1 => {
+                let (variant, value) = object.iter().next().expect("the object has one entry");
+                let value = Cursor::new(value, path.join(PathSegment::Key(variant.clone())));
+                visitor.visit_enum(Variant { variant, value })
+            },
+            value => Err(de::Error::invalid_type(unexpected(value), &"a string or an object with a single key")),
+        }.map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+fn visit_seq<'de, V: Visitor<'de>>(x: Cursor<'de, Value>, visitor: V) -> Result<V::Value, Error> {
+    let elements = x.children().map_err(de::Error::custom)?;
+    let len = elements.len();
+
+    let mut elements = elements.into_iter();
+    let value = visitor.visit_seq(Elements(&mut elements))?;
+
+    match elements.len() {
+        0 => Ok(value),
+        _ => Err(de::Error::invalid_length(len, &"fewer elements in array")),
+    }
+}
+
+fn visit_map<'de, V: Visitor<'de>>(x: Cursor<'de, Value>, visitor: V) -> Result<V::Value, Error> {
+    let value = x.value().map_err(de::Error::custom)?;
+    let Value::Object(object) = value else {
+        return Err(de::Error::invalid_type(unexpected(value), &"an object"));
+    };
+
+    let path = x.path();
+    let mut entries = object.iter()
+        .map(|(key, value)| (key.as_str(), Cursor::new(value, path.join(PathSegment::Key(key.clone())))))
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let value = visitor.visit_map(Entries { entries: &mut entries, value: None })?;
+
+    match entries.len() {
+        0 => Ok(value),
+        _ => Err(de::Error::invalid_length(object.len(), &"fewer elements in map")),
+    }
+}
+
+fn unexpected(value: &Value) -> de::Unexpected<'_> {
+    match value {
+        Value::Null => de::Unexpected::Unit,
+        Value::Bool(b) => de::Unexpected::Bool(*b),
+        Value::Number(_) => de::Unexpected::Other("number"),
+        Value::String(s) => de::Unexpected::Str(s),
+        Value::Array(_) => de::Unexpected::Seq,
+        Value::Object(_) => de::Unexpected::Map,
+    }
+}
+
+struct Elements<'a, 'de>(&'a mut std::vec::IntoIter<Cursor<'de, Value>>);
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        self.0.next().map(|x| seed.deserialize(x)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Entries<'a, 'de> {
+    entries: &'a mut std::vec::IntoIter<(&'de str, Cursor<'de, Value>)>,
+    value: Option<Cursor<'de, Value>>,
+}
+
+impl<'de> de::MapAccess<'de> for Entries<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        let path = value.path().clone();
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some).map_err(|e: Error| e.at(&path))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self.value.take().ok_or_else(|| de::Error::custom("a value was requested before its key"))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct Variant<'de> {
+    variant: &'de str,
+    value: Cursor<'de, Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for Variant<'de> {
+    type Error = Error;
+    type Variant = Cursor<'de, Value>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Cursor<'de, Value>), Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.variant))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Cursor<'de, Value> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+}
@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+#[cfg(feature = "serde_json")]
+use serde_json::{Number, Value};
+
+use crate::Navigable;
+
+/// The comparison operators usable in filter predicates
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    /// Compares two values, numbers are compared by their numeric value regardless of representation.
+    /// Values that have no order (e.g. a string and a number) are only ever unequal
+    #[cfg(feature = "serde_json")]
+    pub fn test(self, lhs: &Value, rhs: &Value) -> bool {
+        let ordering = match (lhs, rhs) {
+            (Value::Number(lhs), Value::Number(rhs)) => compare_numbers(lhs, rhs),
+            (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+            _ if values_equal(lhs, rhs) => Some(Ordering::Equal),
+            _ => None,
+        };
+
+        self.holds(ordering)
+    }
+
+    /// Compares a value of any tree to the literal of a `json_nav!` filter predicate,
+    /// like [`Comparison::test`] does for two json values
+    pub fn test_literal<V: Navigable>(self, lhs: &V, rhs: &Literal<'_>) -> bool {
+        let ordering = match rhs {
+            Literal::Null => lhs.is_null().then_some(Ordering::Equal),
+            Literal::Bool(rhs) => lhs.as_bool().filter(|lhs| lhs == rhs).map(|_| Ordering::Equal),
+            Literal::Str(rhs) => lhs.as_str().map(|lhs| lhs.cmp(rhs)),
+            Literal::Integer(rhs) => match (lhs.as_i128(), lhs.as_u128()) {
+                (Some(lhs), _) => Some(lhs.cmp(rhs)),
+                // only integers above `i128::MAX` are left
+                (None, Some(_)) => Some(Ordering::Greater),
+                (None, None) => lhs.as_f64().and_then(|lhs| lhs.partial_cmp(&(*rhs as f64))),
+            },
+            Literal::Float(rhs) => lhs.as_f64().and_then(|lhs| lhs.partial_cmp(rhs)),
+        };
+
+        self.holds(ordering)
+    }
+
+    fn holds(self, ordering: Option<Ordering>) -> bool {
+        match self {
+            Comparison::Eq => ordering == Some(Ordering::Equal),
+            Comparison::Ne => ordering != Some(Ordering::Equal),
+            Comparison::Lt => ordering == Some(Ordering::Less),
+            Comparison::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Gt => ordering == Some(Ordering::Greater),
+            Comparison::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+/// The scalar a `json_nav!` filter predicate compares to
+#[derive(Clone, Debug, PartialEq)]
+pub enum Literal<'a> {
+    /// Written as `null`, only equal to null values
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    Str(Cow<'a, str>),
+}
+
+impl From<bool> for Literal<'_> {
+    fn from(b: bool) -> Self {
+        Literal::Bool(b)
+    }
+}
+
+impl<'a> From<&'a str> for Literal<'a> {
+    fn from(s: &'a str) -> Self {
+        Literal::Str(Cow::Borrowed(s))
+    }
+}
+
+impl<'a> From<&'a String> for Literal<'a> {
+    fn from(s: &'a String) -> Self {
+        Literal::Str(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for Literal<'_> {
+    fn from(s: String) -> Self {
+        Literal::Str(Cow::Owned(s))
+    }
+}
+
+impl From<f32> for Literal<'_> {
+    fn from(n: f32) -> Self {
+        Literal::Float(n.into())
+    }
+}
+
+impl From<f64> for Literal<'_> {
+    fn from(n: f64) -> Self {
+        Literal::Float(n)
+    }
+}
+
+macro_rules! impl_literal_from_integer {
+    ($($int:ident)*) => {
+        $(
+            impl From<$int> for Literal<'_> {
+                fn from(n: $int) -> Self {
+                    Literal::Integer(n.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_literal_from_integer!(u8 u16 u32 u64 i8 i16 i32 i64 i128);
+
+impl From<usize> for Literal<'_> {
+    fn from(n: usize) -> Self {
+        Literal::Integer(n as i128)
+    }
+}
+
+impl From<isize> for Literal<'_> {
+    fn from(n: isize) -> Self {
+        Literal::Integer(n as i128)
+    }
+}
+
+/// Structural equality, comparing nested numbers by their numeric value
+#[cfg(feature = "serde_json")]
+pub fn values_equal(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => compare_numbers(lhs, rhs) == Some(Ordering::Equal),
+        (Value::Array(lhs), Value::Array(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| values_equal(lhs, rhs))
+        },
+        (Value::Object(lhs), Value::Object(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().all(|(key, lhs)| rhs.get(key).is_some_and(|rhs| values_equal(lhs, rhs)))
+        },
+        _ => lhs == rhs,
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn compare_numbers(lhs: &Number, rhs: &Number) -> Option<Ordering> {
+    if let (Some(lhs), Some(rhs)) = (lhs.as_i64(), rhs.as_i64()) {
+        Some(lhs.cmp(&rhs))
+    } else if let (Some(lhs), Some(rhs)) = (lhs.as_u64(), rhs.as_u64()) {
+        Some(lhs.cmp(&rhs))
+    } else {
+        lhs.as_f64()?.partial_cmp(&rhs.as_f64()?)
+    }
+}
@@ -0,0 +1,625 @@
+use std::borrow::Cow;
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+
+#[cfg(feature = "serde_json")]
+pub use serde_json::{Map, Value};
+#[cfg(feature = "chrono")]
+pub use chrono::{NaiveDate, NaiveTime, TimeDelta};
+#[cfg(feature = "rust_decimal")]
+pub use rust_decimal::Decimal;
+
+#[cfg(feature = "serde_json")]
+pub use crate::de::deserialize;
+pub use crate::filter::{Comparison, Literal};
+#[cfg(feature = "serde_json")]
+pub use mutable::CursorMut;
+#[cfg(feature = "chrono")]
+pub use crate::temporal::{parse_time, Temporal};
+use crate::{FromJsonNav, JsonNavError, NavPath, Navigable, Policy, PathSegment, Segment, SegmentRef};
+
+#[cfg(feature = "serde_json")]
+mod mutable;
+
+/// A value together with the path that was taken to reach it
+pub struct Cursor<'a, V: Navigable> {
+    node: Node<'a, V>,
+    path: NavPath,
+}
+
+/// What a cursor refers to, only the root of a navigation can be an object that isn't a value
+enum Node<'a, V: Navigable> {
+    Value(&'a V),
+    Object(&'a dyn RootObject<V>),
+}
+
+impl<V: Navigable> Clone for Node<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: Navigable> Copy for Node<'_, V> {}
+
+impl<V: Navigable> Clone for Cursor<'_, V> {
+    fn clone(&self) -> Self {
+        Cursor { node: self.node, path: self.path.clone() }
+    }
+}
+
+impl<'a, V: Navigable> Cursor<'a, V> {
+    pub fn root(value: &'a V, name: impl Into<Cow<'static, str>>) -> Self {
+        Cursor { node: Node::Value(value), path: NavPath::new(name).with_style(V::PATH_STYLE) }
+    }
+
+    pub fn root_object(object: &'a dyn RootObject<V>, name: impl Into<Cow<'static, str>>) -> Self {
+        Cursor { node: Node::Object(object), path: NavPath::new(name).with_style(V::PATH_STYLE) }
+    }
+
+    #[cfg(feature = "serde_json")]
+    pub(crate) fn new(value: &'a V, path: NavPath) -> Self {
+        Cursor { node: Node::Value(value), path }
+    }
+
+    pub fn get<S: Segment + ?Sized>(mut self, segment: &S) -> Result<Self, JsonNavError> {
+        let segment = segment.segment_ref();
+
+        match self.lookup(segment) {
+            Some((step, value)) => {
+                self.path.push(step);
+                Ok(Cursor { node: Node::Value(value), path: self.path })
+            },
+            None => {
+                let len = self.value().ok().and_then(Navigable::array_len);
+                Err(missing(self.path, segment, len))
+            },
+        }
+    }
+
+    fn lookup(&self, segment: SegmentRef<'_>) -> Option<(PathSegment, &'a V)> {
+        match (self.node, segment) {
+            (Node::Value(value), segment) => segment.lookup(value),
+            (Node::Object(object), SegmentRef::Key(key)) => Some((PathSegment::Key(key.to_owned()), object.get_key(key)?)),
+            (Node::Object(_), SegmentRef::Index(_)) => None,
+        }
+    }
+
+    /// The elements of an array from `start` up to, but excluding, `end`.
+    /// Negative bounds count from the end of the array, bounds past either end are clamped
+    pub fn slice(self, start: Option<i128>, end: Option<i128>) -> Result<Vec<Self>, JsonNavError> {
+        let value = self.value().ok().filter(|value| value.array_len().is_some());
+        let Some(value) = value else {
+            return Err(JsonNavError::TypeMismatch { expected: "array" });
+        };
+        let len = value.array_len().expect("the value was just checked to be an array");
+
+        let clamp = |bound: i128| if bound < 0 {
+            len.saturating_sub(usize::try_from(bound.unsigned_abs()).unwrap_or(usize::MAX))
+        } else {
+            usize::try_from(bound).unwrap_or(usize::MAX).min(len)
+        };
+
+        let start = start.map_or(0, clamp);
+        let end = end.map_or(len, clamp);
+
+        Ok((start..end)
+            .filter_map(|index| value.get_index(index).map(|value| (index, value)))
+            .map(|(index, value)| Cursor { node: Node::Value(value), path: self.path.join(PathSegment::Index(index)) })
+            .collect())
+    }
+
+    /// All elements of an array or all values of an object
+    pub fn children(self) -> Result<Vec<Self>, JsonNavError> {
+        let children = match self.node {
+            Node::Value(value) => value.children(),
+            Node::Object(object) => Some(object.children()),
+        };
+        let children = children.ok_or(JsonNavError::TypeMismatch { expected: "array or object" })?;
+
+        Ok(children.into_iter()
+            .map(|(step, value)| Cursor { node: Node::Value(value), path: self.path.join(step) })
+            .collect())
+    }
+
+    /// All values `segment` refers to in this value or any of its descendants, in document order
+    pub fn descendants<S: Segment + ?Sized>(self, segment: &S) -> Matches<'a, V> {
+        let mut matches = Vec::new();
+        self.collect_descendants(segment, &mut matches);
+        Matches(matches)
+    }
+
+    fn collect_descendants<S: Segment + ?Sized>(self, segment: &S, matches: &mut Vec<Self>) {
+        if let Some((step, value)) = self.lookup(segment.segment_ref()) {
+            matches.push(Cursor { node: Node::Value(value), path: self.path.join(step) });
+        }
+
+        if let Ok(children) = self.children() {
+            for child in children {
+                child.collect_descendants(segment, matches);
+            }
+        }
+    }
+
+    /// All elements of an array or values of an object satisfying `test`,
+    /// it is an error if none of them do
+    pub fn filter<F>(self, predicate: &'static str, mut test: F) -> Result<Vec<Self>, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> bool,
+    {
+        let path = self.path.clone();
+        let candidates = self.children()?;
+        let count = candidates.len();
+
+        let matches: Vec<_> = candidates.into_iter()
+            .filter(|x| test(x.clone()))
+            .collect();
+
+        if matches.is_empty() {
+            Err(JsonNavError::Filtered { path, predicate, candidates: count })
+        } else {
+            Ok(matches)
+        }
+    }
+
+    /// Navigates along the first of the given alternative paths that can be resolved
+    pub fn first_of<R, const N: usize>(self, alternatives: [&dyn Fn(Self) -> Result<R, JsonNavError>; N]) -> Result<R, JsonNavError> {
+        let mut attempts = Vec::with_capacity(N);
+
+        for alternative in alternatives {
+            match alternative(self.clone()) {
+                Ok(found) => return Ok(found),
+                Err(e) => attempts.push(e),
+            }
+        }
+
+        Err(JsonNavError::NoAlternative { attempts })
+    }
+
+    /// The value this cursor refers to, a root object isn't one
+    pub fn value(&self) -> Result<&'a V, JsonNavError> {
+        match self.node {
+            Node::Value(value) => Ok(value),
+            Node::Object(_) => Err(JsonNavError::TypeMismatch { expected: "value" }),
+        }
+    }
+
+    pub fn path(&self) -> &NavPath {
+        &self.path
+    }
+
+    pub fn into_value(self) -> Result<&'a V, JsonNavError> {
+        self.value()
+    }
+}
+
+/// Lets `json_nav!` take values and references to them alike, as method calls auto-deref their receiver,
+/// as well as objects that aren't values of their tree like a `toml::Table`
+pub trait Root {
+    type Value: Navigable;
+
+    fn json_nav_root(&self, name: &'static str) -> Cursor<'_, Self::Value>;
+}
+
+impl<V: Navigable> Root for V {
+    type Value = V;
+
+    fn json_nav_root(&self, name: &'static str) -> Cursor<'_, V> {
+        Cursor::root(self, name)
+    }
+}
+
+/// An object that can be the root of a navigation without being a value of `V`,
+/// it can be navigated into but not converted itself, apart from `; as object`
+pub trait RootObject<V: Navigable> {
+    fn get_key(&self, key: &str) -> Option<&V>;
+
+    fn children(&self) -> Vec<(PathSegment, &V)>;
+
+    fn as_object(&self) -> &V::Object;
+}
+
+#[cfg(feature = "toml")]
+impl Root for toml::Table {
+    type Value = toml::Value;
+
+    fn json_nav_root(&self, name: &'static str) -> Cursor<'_, toml::Value> {
+        Cursor::root_object(self, name)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl RootObject<toml::Value> for toml::Table {
+    fn get_key(&self, key: &str) -> Option<&toml::Value> {
+        self.get(key)
+    }
+
+    fn children(&self) -> Vec<(PathSegment, &toml::Value)> {
+        self.iter().map(|(key, value)| (PathSegment::Key(key.clone()), value)).collect()
+    }
+
+    fn as_object(&self) -> &toml::Table {
+        self
+    }
+}
+
+/// The error for a `segment` that could not be found in `value` at `path`
+fn missing(mut path: NavPath, segment: SegmentRef<'_>, array_len: Option<usize>) -> JsonNavError {
+    match (segment, array_len) {
+        (SegmentRef::Index(index), Some(len)) => JsonNavError::OutOfBounds { path, index, len },
+        // indexing from the end only makes sense for arrays, so there is no path to report
+        (SegmentRef::Index(index), _) if index < 0 => JsonNavError::TypeMismatch { expected: "array" },
+        // integer segments look up integer keys in maps
+        (SegmentRef::Index(index), _) => {
+            path.push(PathSegment::Key(index.to_string()));
+            JsonNavError::Navigation { path }
+        },
+        (SegmentRef::Key(key), _) => {
+            path.push(PathSegment::Key(key.to_owned()));
+            JsonNavError::Navigation { path }
+        },
+    }
+}
+
+/// The intermediate result of a navigation, either a single [`Cursor`],
+/// a `Vec` of them after a fan-out segment or [`Matches`] after a recursive descent
+pub trait Selection<'a, V: Navigable + 'a>: Sized {
+    /// The selection resulting from replacing every cursor by the selection `R`
+    type FlatMap<R: Selection<'a, V>>: Selection<'a, V>;
+
+    /// The selection holding any number of cursors that is at least as informative as `Self`
+    type Many: ManySelection<'a, V>;
+
+    /// The final result of converting every cursor to a `T`
+    type Map<T>;
+
+    fn flat_map<R, F>(self, f: F) -> Result<Self::FlatMap<R>, JsonNavError>
+    where
+        R: Selection<'a, V>,
+        F: FnMut(Cursor<'a, V>) -> Result<R, JsonNavError>;
+
+    fn map<T, F>(self, f: F) -> Result<Self::Map<T>, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> Result<T, JsonNavError>;
+
+    fn into_cursors(self) -> Vec<Cursor<'a, V>>;
+
+    fn get<S: Segment + ?Sized>(self, segment: &S) -> Result<Self::FlatMap<Cursor<'a, V>>, JsonNavError> {
+        self.flat_map(|x| x.get(segment))
+    }
+
+    fn index<I: IndexSelector<'a, V> + ?Sized>(self, selector: &I) -> Result<Self::FlatMap<I::Selection>, JsonNavError> {
+        self.flat_map(|x| selector.select(x))
+    }
+
+    fn wildcard(self) -> Result<Self::FlatMap<Vec<Cursor<'a, V>>>, JsonNavError> {
+        self.flat_map(Cursor::children)
+    }
+
+    fn filter<F>(self, predicate: &'static str, mut test: F) -> Result<Self::FlatMap<Vec<Cursor<'a, V>>>, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> bool,
+    {
+        self.flat_map(|x| x.filter(predicate, &mut test))
+    }
+
+    fn descendants<S: Segment + ?Sized>(self, segment: &S) -> Result<Self::FlatMap<Matches<'a, V>>, JsonNavError> {
+        self.flat_map(|x| Ok(x.descendants(segment)))
+    }
+
+    fn first_of<R, const N: usize>(self, alternatives: [&dyn Fn(Cursor<'a, V>) -> Result<R, JsonNavError>; N]) -> Result<Self::FlatMap<R>, JsonNavError>
+    where
+        R: Selection<'a, V>,
+    {
+        self.flat_map(|x| x.first_of(alternatives))
+    }
+}
+
+/// Whether any value selected by a filter sub-path compares to `rhs` as requested
+pub fn test_comparison<'a, V: Navigable + 'a, R: Selection<'a, V>>(lhs: Result<R, JsonNavError>, comparison: Comparison, rhs: &Literal<'_>) -> bool {
+    lhs.map(|lhs| lhs.into_cursors().iter().any(|x| x.value().is_ok_and(|value| comparison.test_literal(value, rhs))))
+        .unwrap_or(false)
+}
+
+/// Whether a filter sub-path selects anything
+pub fn test_existence<'a, V: Navigable + 'a, R: Selection<'a, V>>(selected: Result<R, JsonNavError>) -> bool {
+    selected.is_ok_and(|selected| !selected.into_cursors().is_empty())
+}
+
+/// A selection of a single value. `?` and the `or` defaults apply to the navigation as a whole, after
+/// a fan-out one missing element would throw away all the values that were found
+#[diagnostic::on_unimplemented(
+    message = "`?` and `or` can't follow segments that select several values",
+    label = "the path fans out with `*`, `..`, a slice or a filter",
+    note = "navigate into each of the selected values with `?` or `or` instead",
+)]
+pub trait SingleSelection {}
+
+impl<V: Navigable> SingleSelection for Cursor<'_, V> {}
+
+/// Requires a navigation to select a single value, for the `or_any` and `or_else_any` defaults
+pub fn single<S: SingleSelection>(selected: Result<S, JsonNavError>) -> Result<S, JsonNavError> {
+    selected
+}
+
+/// Turns the error of a navigation into `None` if the path does not exist, for the optional `?` form
+pub fn optional<S: SingleSelection>(selected: Result<S, JsonNavError>) -> Result<Option<S>, JsonNavError> {
+    match selected {
+        Ok(selected) => Ok(Some(selected)),
+        Err(e) if e.is_missing() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Converts the value under `x` with its [`FromJsonNav`] implementation
+pub fn convert<'a, T: FromJsonNav<'a, V>, V: Navigable>(x: Cursor<'a, V>) -> Result<T, JsonNavError> {
+    T::from_json_nav(x.value()?)
+}
+
+/// The `; as <type> with <policy>` conversion
+pub fn convert_with<'a, T: FromJsonNav<'a, V>, V: Navigable>(x: Cursor<'a, V>, policy: &Policy) -> Result<T, JsonNavError> {
+    T::from_json_nav_with(x.value()?, policy)
+}
+
+/// The `; as object` conversion
+pub fn as_object<V: Navigable>(x: Cursor<'_, V>) -> Result<&V::Object, JsonNavError> {
+    match x.node {
+        Node::Value(value) => value.as_object().ok_or(JsonNavError::TypeMismatch { expected: "object" }),
+        Node::Object(object) => Ok(object.as_object()),
+    }
+}
+
+/// The `; as array` conversion
+pub fn as_array<V: Navigable>(x: Cursor<'_, V>) -> Result<&V::Array, JsonNavError> {
+    x.value()?.as_array().ok_or(JsonNavError::TypeMismatch { expected: "array" })
+}
+
+/// The `; as number_str` conversion
+pub fn as_number_str<V: Navigable>(x: Cursor<'_, V>) -> Result<Cow<'_, str>, JsonNavError> {
+    x.value()?.as_number_str().ok_or(JsonNavError::TypeMismatch { expected: "number" })
+}
+
+/// The `; as enum { .. }` conversion, finds the value `variants` pairs with the string under `x`
+pub fn match_variant<T, V: Navigable, const N: usize>(x: Cursor<'_, V>, variants: [(&'static str, T); N]) -> Result<T, JsonNavError> {
+    let value = x.value()?.as_str().ok_or(JsonNavError::TypeMismatch { expected: "str" })?;
+    let allowed = variants.each_ref().map(|(name, _)| *name);
+
+    variants.into_iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, variant)| variant)
+        .ok_or_else(|| JsonNavError::UnknownVariant { path: x.path.clone(), value: value.to_owned(), allowed: allowed.to_vec() })
+}
+
+/// Values that can carry a tag, `; as tagged` converts them to `Tagged::Tagged`
+pub trait Tagged<'a>: Navigable + Sized + 'a {
+    type Tagged: FromJsonNav<'a, Self>;
+}
+
+#[cfg(feature = "serde_yaml")]
+impl<'a> Tagged<'a> for serde_yaml::Value {
+    type Tagged = &'a serde_yaml::value::TaggedValue;
+}
+
+#[cfg(feature = "ciborium")]
+impl<'a> Tagged<'a> for ciborium::Value {
+    type Tagged = (u64, &'a ciborium::Value);
+}
+
+// msgpack ext values are an `i8` type tag and the bytes it applies to
+#[cfg(feature = "rmpv")]
+impl<'a> Tagged<'a> for rmpv::Value {
+    type Tagged = (i8, &'a [u8]);
+}
+
+/// The `; as tagged` conversion
+pub fn as_tagged<'a, V: Tagged<'a>>(x: Cursor<'a, V>) -> Result<V::Tagged, JsonNavError> {
+    convert(x)
+}
+
+#[cfg(feature = "chrono")]
+pub type DateTime = chrono::DateTime<chrono::FixedOffset>;
+
+/// Values `; as datetime` converts, to the format's own datetimes if it has them
+pub trait Datetimes<'a>: Navigable + Sized + 'a {
+    type Datetime;
+
+    fn as_datetime(x: Cursor<'a, Self>) -> Result<Self::Datetime, JsonNavError>;
+}
+
+#[cfg(feature = "toml")]
+impl<'a> Datetimes<'a> for toml::Value {
+    type Datetime = &'a toml::value::Datetime;
+
+    fn as_datetime(x: Cursor<'a, Self>) -> Result<&'a toml::value::Datetime, JsonNavError> {
+        convert(x)
+    }
+}
+
+macro_rules! impl_chrono_datetimes {
+    ($($feature:literal $value:ty),*) => {
+        $(
+            #[cfg(all(feature = "chrono", feature = $feature))]
+            impl<'a> Datetimes<'a> for $value {
+                type Datetime = DateTime;
+
+                fn as_datetime(x: Cursor<'a, Self>) -> Result<DateTime, JsonNavError> {
+                    parse_time(x, crate::TimeFormat::Iso8601)
+                }
+            }
+        )*
+    };
+}
+
+impl_chrono_datetimes!("serde_json" serde_json::Value, "serde_yaml" serde_yaml::Value, "ciborium" ciborium::Value, "rmpv" rmpv::Value);
+
+/// The `; as datetime` conversion
+pub fn as_datetime<'a, V: Datetimes<'a>>(x: Cursor<'a, V>) -> Result<V::Datetime, JsonNavError> {
+    V::as_datetime(x)
+}
+
+/// A [`Selection`] of any number of cursors
+pub trait ManySelection<'a, V: Navigable + 'a>: Selection<'a, V> {
+    fn from_cursors(cursors: Vec<Cursor<'a, V>>) -> Self;
+}
+
+impl<'a, V: Navigable + 'a> Selection<'a, V> for Cursor<'a, V> {
+    type FlatMap<R: Selection<'a, V>> = R;
+    type Many = Vec<Cursor<'a, V>>;
+    type Map<T> = T;
+
+    fn flat_map<R, F>(self, mut f: F) -> Result<R, JsonNavError>
+    where
+        R: Selection<'a, V>,
+        F: FnMut(Cursor<'a, V>) -> Result<R, JsonNavError>,
+    {
+        f(self)
+    }
+
+    fn map<T, F>(self, mut f: F) -> Result<T, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> Result<T, JsonNavError>,
+    {
+        f(self)
+    }
+
+    fn into_cursors(self) -> Vec<Cursor<'a, V>> {
+        vec![self]
+    }
+}
+
+impl<'a, V: Navigable + 'a> Selection<'a, V> for Vec<Cursor<'a, V>> {
+    type FlatMap<R: Selection<'a, V>> = R::Many;
+    type Many = Self;
+    type Map<T> = Vec<T>;
+
+    fn flat_map<R, F>(self, f: F) -> Result<R::Many, JsonNavError>
+    where
+        R: Selection<'a, V>,
+        F: FnMut(Cursor<'a, V>) -> Result<R, JsonNavError>,
+    {
+        flat_map_cursors(self, f).map(R::Many::from_cursors)
+    }
+
+    fn map<T, F>(self, f: F) -> Result<Vec<T>, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> Result<T, JsonNavError>,
+    {
+        self.into_iter().map(f).collect()
+    }
+
+    fn into_cursors(self) -> Vec<Cursor<'a, V>> {
+        self
+    }
+}
+
+impl<'a, V: Navigable + 'a> ManySelection<'a, V> for Vec<Cursor<'a, V>> {
+    fn from_cursors(cursors: Vec<Cursor<'a, V>>) -> Self {
+        cursors
+    }
+}
+
+/// The cursors found by a recursive descent, their paths are kept in the final result
+pub struct Matches<'a, V: Navigable>(Vec<Cursor<'a, V>>);
+
+impl<'a, V: Navigable + 'a> Selection<'a, V> for Matches<'a, V> {
+    type FlatMap<R: Selection<'a, V>> = Self;
+    type Many = Self;
+    type Map<T> = Vec<(NavPath, T)>;
+
+    fn flat_map<R, F>(self, f: F) -> Result<Self, JsonNavError>
+    where
+        R: Selection<'a, V>,
+        F: FnMut(Cursor<'a, V>) -> Result<R, JsonNavError>,
+    {
+        flat_map_cursors(self.0, f).map(Matches)
+    }
+
+    fn map<T, F>(self, mut f: F) -> Result<Vec<(NavPath, T)>, JsonNavError>
+    where
+        F: FnMut(Cursor<'a, V>) -> Result<T, JsonNavError>,
+    {
+        self.0.into_iter()
+            .map(|x| {
+                let path = x.path.clone();
+                f(x).map(|value| (path, value))
+            })
+            .collect()
+    }
+
+    fn into_cursors(self) -> Vec<Cursor<'a, V>> {
+        self.0
+    }
+}
+
+impl<'a, V: Navigable + 'a> ManySelection<'a, V> for Matches<'a, V> {
+    fn from_cursors(cursors: Vec<Cursor<'a, V>>) -> Self {
+        Matches(cursors)
+    }
+}
+
+fn flat_map_cursors<'a, V: Navigable + 'a, R, F>(cursors: Vec<Cursor<'a, V>>, mut f: F) -> Result<Vec<Cursor<'a, V>>, JsonNavError>
+where
+    R: Selection<'a, V>,
+    F: FnMut(Cursor<'a, V>) -> Result<R, JsonNavError>,
+{
+    let mut flattened = Vec::with_capacity(cursors.len());
+
+    for cursor in cursors {
+        flattened.extend(f(cursor)?.into_cursors());
+    }
+
+    Ok(flattened)
+}
+
+/// The contents of a `[...]` segment, either a single index or a range of indices
+pub trait IndexSelector<'a, V: Navigable + 'a> {
+    type Selection: Selection<'a, V>;
+
+    fn select(&self, cursor: Cursor<'a, V>) -> Result<Self::Selection, JsonNavError>;
+}
+
+impl<'a, V: Navigable + 'a> IndexSelector<'a, V> for RangeFull {
+    type Selection = Vec<Cursor<'a, V>>;
+
+    fn select(&self, cursor: Cursor<'a, V>) -> Result<Vec<Cursor<'a, V>>, JsonNavError> {
+        cursor.slice(None, None)
+    }
+}
+
+macro_rules! impl_index_selector {
+    ($($int:ty),+) => {
+        $(
+            impl<'a, V: Navigable + 'a> IndexSelector<'a, V> for $int {
+                type Selection = Cursor<'a, V>;
+
+                fn select(&self, cursor: Cursor<'a, V>) -> Result<Cursor<'a, V>, JsonNavError> {
+                    cursor.get(self)
+                }
+            }
+
+            impl<'a, V: Navigable + 'a> IndexSelector<'a, V> for Range<$int> {
+                type Selection = Vec<Cursor<'a, V>>;
+
+                fn select(&self, cursor: Cursor<'a, V>) -> Result<Vec<Cursor<'a, V>>, JsonNavError> {
+                    cursor.slice(Some(self.start as i128), Some(self.end as i128))
+                }
+            }
+
+            impl<'a, V: Navigable + 'a> IndexSelector<'a, V> for RangeFrom<$int> {
+                type Selection = Vec<Cursor<'a, V>>;
+
+                fn select(&self, cursor: Cursor<'a, V>) -> Result<Vec<Cursor<'a, V>>, JsonNavError> {
+                    cursor.slice(Some(self.start as i128), None)
+                }
+            }
+
+            impl<'a, V: Navigable + 'a> IndexSelector<'a, V> for RangeTo<$int> {
+                type Selection = Vec<Cursor<'a, V>>;
+
+                fn select(&self, cursor: Cursor<'a, V>) -> Result<Vec<Cursor<'a, V>>, JsonNavError> {
+                    cursor.slice(None, Some(self.end as i128))
+                }
+            }
+        )+
+    };
+}
+
+impl_index_selector!(usize, u8, u16, u32, u64, isize, i8, i16, i32, i64);
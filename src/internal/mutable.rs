@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use super::missing;
+use crate::{JsonNavError, Navigable, NavPath, PathSegment, Segment, SegmentRef};
+
+/// A mutable value together with the path that was taken to reach it, used by `json_nav_mut!`
+pub struct CursorMut<'a> {
+    value: &'a mut Value,
+    path: NavPath,
+    /// How many segments at the end of `path` `entry` has yet to create below `value`
+    pending: usize,
+}
+
+impl<'a> CursorMut<'a> {
+    pub fn root(value: &'a mut Value, name: impl Into<Cow<'static, str>>) -> Self {
+        CursorMut { value, path: NavPath::new(name), pending: 0 }
+    }
+
+    pub fn get<S: Segment + ?Sized>(self, segment: &S) -> Result<Self, JsonNavError> {
+        let segment = segment.segment_ref();
+
+        // looking up twice keeps the borrow checker from tying the error to the mutable borrow
+        if segment.lookup(self.value).is_none() {
+            return Err(missing(self.path, segment, self.value.array_len()));
+        }
+
+        let CursorMut { value, mut path, pending } = self;
+        debug_assert_eq!(0, pending, "get() after entry()");
+        let (step, value) = segment.lookup_mut(value).expect("the segment was just looked up");
+        path.push(step);
+
+        Ok(CursorMut { value, path, pending })
+    }
+
+    /// Like [`CursorMut::get`], but creates the value `segment` refers to as `null` if it is missing.
+    /// A `null` value is replaced by an object or array as needed, an index may append to an array.
+    /// Nothing is created before [`CursorMut::into_value`], so a path that fails part way leaves the value untouched
+    pub fn entry<S: Segment + ?Sized>(self, segment: &S) -> Result<Self, JsonNavError> {
+        let CursorMut { value, mut path, pending } = self;
+        let segment = segment.segment_ref();
+
+        // below a missing or `null` value everything is created, and a new array can only start at its first index
+        if pending > 0 || value.is_null() {
+            let step = match segment {
+                SegmentRef::Key(key) => PathSegment::Key(key.to_owned()),
+                SegmentRef::Index(0) => PathSegment::Index(0),
+                SegmentRef::Index(index) => return Err(JsonNavError::OutOfBounds { path, index, len: 0 }),
+            };
+
+            path.push(step);
+            return Ok(CursorMut { value, path, pending: pending + 1 });
+        }
+
+        // looking up twice keeps the borrow checker from tying the missing case to the mutable borrow
+        if segment.lookup_mut(value).is_some() {
+            let (step, value) = segment.lookup_mut(value).expect("the segment was just looked up");
+            path.push(step);
+            return Ok(CursorMut { value, path, pending });
+        }
+
+        let step = match (segment, &*value) {
+            (SegmentRef::Key(key), Value::Object(_)) => PathSegment::Key(key.to_owned()),
+            (SegmentRef::Index(index), Value::Array(array)) if usize::try_from(index) == Ok(array.len()) => PathSegment::Index(array.len()),
+            (SegmentRef::Index(index), Value::Array(array)) => return Err(JsonNavError::OutOfBounds { path, index, len: array.len() }),
+            (SegmentRef::Key(_), _) => return Err(JsonNavError::Blocked { path, expected: "object" }),
+            (SegmentRef::Index(_), _) => return Err(JsonNavError::Blocked { path, expected: "array" }),
+        };
+
+        path.push(step);
+        Ok(CursorMut { value, path, pending: 1 })
+    }
+
+    /// Removes the value `segment` refers to from this array or object if `convert` accepts it
+    pub fn remove<S, T, F>(self, segment: &S, (expected, convert): (&'static str, F)) -> Result<T, JsonNavError>
+    where
+        S: Segment + ?Sized,
+        F: FnOnce(Value) -> Result<T, Value>,
+    {
+        self.take_with(segment, expected, convert, true)
+    }
+
+    /// Replaces the value `segment` refers to with `null` if `convert` accepts it
+    pub fn take<S, T, F>(self, segment: &S, (expected, convert): (&'static str, F)) -> Result<T, JsonNavError>
+    where
+        S: Segment + ?Sized,
+        F: FnOnce(Value) -> Result<T, Value>,
+    {
+        self.take_with(segment, expected, convert, false)
+    }
+
+    fn take_with<S, T, F>(self, segment: &S, expected: &'static str, convert: F, remove: bool) -> Result<T, JsonNavError>
+    where
+        S: Segment + ?Sized,
+        F: FnOnce(Value) -> Result<T, Value>,
+    {
+        let segment = segment.segment_ref();
+
+        if segment.lookup(self.value).is_none() {
+            return Err(missing(self.path, segment, self.value.array_len()));
+        }
+
+        let (step, slot) = segment.lookup_mut(self.value).expect("the segment was just looked up");
+        let taken = match convert(std::mem::take(slot)) {
+            Ok(taken) => taken,
+            Err(value) => {
+                *slot = value;
+                return Err(JsonNavError::TypeMismatch { expected });
+            },
+        };
+
+        if remove {
+            match (step, self.value) {
+                (PathSegment::Key(key), Value::Object(object)) => { object.remove(&key); },
+                (PathSegment::Index(index), Value::Array(array)) => { array.remove(index); },
+                _ => unreachable!("the segment was looked up in an object or array"),
+            }
+        }
+
+        Ok(taken)
+    }
+
+    /// The value this cursor refers to, creating what `entry` found missing on the way
+    pub fn into_value(self) -> &'a mut Value {
+        let segments = self.path.segments();
+
+        segments[segments.len() - self.pending..].iter().fold(self.value, |value, step| match step {
+            PathSegment::Key(key) => {
+                if value.is_null() {
+                    *value = Value::Object(Map::new());
+                }
+
+                let Value::Object(object) = value else { unreachable!("entry() only creates in objects or null") };
+                object.entry(key.as_str()).or_insert(Value::Null)
+            },
+            PathSegment::Index(_) => {
+                if value.is_null() {
+                    *value = Value::Array(Vec::new());
+                }
+
+                let Value::Array(array) = value else { unreachable!("entry() only creates in arrays or null") };
+                array.push(Value::Null);
+                array.last_mut().expect("an element was just pushed")
+            },
+        })
+    }
+}
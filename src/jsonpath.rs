@@ -0,0 +1,450 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::filter::Comparison;
+use crate::internal::Cursor;
+use crate::path::resolve_index;
+use crate::{JsonNavError, NavPath, PathSegment};
+
+mod iregexp;
+mod parser;
+
+use iregexp::IRegexp;
+
+/// A JSONPath query as specified by RFC 9535, for paths that are only known at runtime
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::JsonPath;
+///
+/// let value = json!({
+///     "store": {
+///         "book": [
+///             { "title": "Sayings of the Century", "price": 8.95 },
+///             { "title": "Moby Dick", "price": 8.99, "isbn": "0-553-21311-3" },
+///             { "title": "The Lord of the Rings", "price": 22.99, "isbn": "0-395-19395-8" }
+///         ]
+///     }
+/// });
+///
+/// let path = JsonPath::parse("$.store.book[?@.isbn && @.price < 10].title").unwrap();
+/// let titles: Vec<_> = path.query(&value)
+///     .into_iter()
+///     .map(|(path, title)| (path.to_normalized(), title.as_str().unwrap()))
+///     .collect();
+/// assert_eq!(vec![("$['store']['book'][1]['title']".to_owned(), "Moby Dick")], titles);
+///
+/// let error = JsonPath::parse("$.store.bicycle.color").unwrap().query_first(&value);
+/// assert_eq!("could not navigate to $.store.bicycle", error.unwrap_err().to_string());
+/// ```
+#[derive(Clone, Debug)]
+pub struct JsonPath {
+    source: String,
+    query: Query,
+}
+
+impl JsonPath {
+    pub fn parse(query: &str) -> Result<Self, JsonNavError> {
+        Ok(JsonPath { source: query.to_owned(), query: parser::parse(query)? })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// All nodes selected by this query in document order, together with their paths.
+    /// Selecting nothing is not an error
+    pub fn query<'a>(&self, value: &'a Value) -> Vec<(NavPath, &'a Value)> {
+        self.query.select(value, Node::root(value))
+            .into_iter()
+            .map(|node| (node.path, node.value))
+            .collect()
+    }
+
+    /// The first node selected by this query,
+    /// if there is none the error names the location at which the query stopped matching
+    pub fn query_first<'a>(&self, value: &'a Value) -> Result<(NavPath, &'a Value), JsonNavError> {
+        let mut nodes = vec![Node::root(value)];
+
+        for segment in &self.query.segments {
+            let selected = segment.select(value, &nodes);
+
+            if selected.is_empty() {
+                return Err(segment.unmatched(nodes.swap_remove(0)));
+            }
+            nodes = selected;
+        }
+
+        let node = nodes.swap_remove(0);
+        Ok((node.path, node.value))
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonNavError;
+
+    fn from_str(query: &str) -> Result<Self, JsonNavError> {
+        JsonPath::parse(query)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Clone)]
+struct Node<'a> {
+    path: NavPath,
+    value: &'a Value,
+}
+
+impl<'a> Node<'a> {
+    fn root(value: &'a Value) -> Self {
+        Node { path: NavPath::new("$"), value }
+    }
+
+    fn child(&self, segment: PathSegment, value: &'a Value) -> Self {
+        Node { path: self.path.join(segment), value }
+    }
+
+    fn children(&self) -> Vec<Self> {
+        match self.value {
+            Value::Array(array) => array.iter()
+                .enumerate()
+                .map(|(index, value)| self.child(PathSegment::Index(index), value))
+                .collect(),
+            Value::Object(object) => object.iter()
+                .map(|(key, value)| self.child(PathSegment::Key(key.clone()), value))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// This node followed by all of its descendants in document order
+    fn descendants(self) -> Vec<Self> {
+        let mut descendants = Vec::new();
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            stack.extend(node.children().into_iter().rev());
+            descendants.push(node);
+        }
+
+        descendants
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Query {
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    fn select<'a>(&self, root: &'a Value, current: Node<'a>) -> Vec<Node<'a>> {
+        let start = if self.absolute { Node::root(root) } else { current };
+
+        self.segments.iter().fold(vec![start], |nodes, segment| segment.select(root, &nodes))
+    }
+
+    /// Whether this query can select at most one node
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && matches!(segment.selectors.as_slice(), [Selector::Name(_) | Selector::Index(_)])
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Segment {
+    source: String,
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+impl Segment {
+    fn select<'a>(&self, root: &'a Value, nodes: &[Node<'a>]) -> Vec<Node<'a>> {
+        let mut selected = Vec::new();
+
+        for node in nodes {
+            let candidates = if self.descendant { node.clone().descendants() } else { vec![node.clone()] };
+
+            for candidate in &candidates {
+                for selector in &self.selectors {
+                    selector.select(root, candidate, &mut selected);
+                }
+            }
+        }
+
+        selected
+    }
+
+    /// The error describing why this segment selected nothing from `node`
+    fn unmatched(&self, node: Node<'_>) -> JsonNavError {
+        let cursor = Cursor::new(node.value, node.path.clone());
+
+        let error = match (self.descendant, self.selectors.as_slice()) {
+            (false, [Selector::Name(name)]) => cursor.get(name).err(),
+            (false, [Selector::Index(index)]) => cursor.get(index).err(),
+            _ => None,
+        };
+
+        error.unwrap_or_else(|| JsonNavError::NoMatch { path: node.path, segment: self.source.clone() })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice { start: Option<i64>, end: Option<i64>, step: Option<i64> },
+    Filter(LogicalExpr),
+}
+
+impl Selector {
+    fn select<'a>(&self, root: &'a Value, node: &Node<'a>, selected: &mut Vec<Node<'a>>) {
+        match (self, node.value) {
+            (Selector::Name(name), Value::Object(object)) => {
+                if let Some(value) = object.get(name) {
+                    selected.push(node.child(PathSegment::Key(name.clone()), value));
+                }
+            },
+            (Selector::Wildcard, _) => selected.extend(node.children()),
+            (Selector::Index(index), Value::Array(array)) => {
+                let index = resolve_index(i128::from(*index), array.len());
+
+                if let Some(index) = index {
+                    selected.push(node.child(PathSegment::Index(index), &array[index]));
+                }
+            },
+            (Selector::Slice { start, end, step }, Value::Array(array)) => {
+                selected.extend(slice_indices(*start, *end, *step, array.len())
+                    .map(|index| node.child(PathSegment::Index(index), &array[index])));
+            },
+            (Selector::Filter(filter), _) => {
+                selected.extend(node.children().into_iter().filter(|child| filter.test(root, child)));
+            },
+            _ => {},
+        }
+    }
+}
+
+/// The indices selected by a slice as specified in RFC 9535 section 2.3.4.2.2
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: Option<i64>, len: usize) -> impl Iterator<Item = usize> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    let normalize = |index: i64| if index >= 0 { index } else { len + index };
+
+    let (lower, upper) = if step >= 0 {
+        let start = start.map_or(0, normalize);
+        let end = end.map_or(len, normalize);
+        (start.clamp(0, len), end.clamp(0, len))
+    } else {
+        let start = start.map_or(len - 1, normalize);
+        let end = end.map_or(-len - 1, normalize);
+        (end.clamp(-1, len - 1), start.clamp(-1, len - 1))
+    };
+
+    let indices: Box<dyn Iterator<Item = i64>> = match step {
+        0 => Box::new(std::iter::empty()),
+        1.. => Box::new((lower..upper).step_by(step as usize)),
+        _ => Box::new(((lower + 1)..=upper).rev().step_by(step.unsigned_abs() as usize)),
+    };
+
+    indices.map(|index| index as usize)
+}
+
+#[derive(Clone, Debug)]
+enum LogicalExpr {
+    Or(Vec<LogicalExpr>),
+    And(Vec<LogicalExpr>),
+    Not(Box<LogicalExpr>),
+    Comparison(Operand, Comparison, Operand),
+    Exists(Query),
+    Function(FunctionExpr),
+}
+
+impl LogicalExpr {
+    fn test<'a>(&self, root: &'a Value, current: &Node<'a>) -> bool {
+        match self {
+            LogicalExpr::Or(exprs) => exprs.iter().any(|expr| expr.test(root, current)),
+            LogicalExpr::And(exprs) => exprs.iter().all(|expr| expr.test(root, current)),
+            LogicalExpr::Not(expr) => !expr.test(root, current),
+            LogicalExpr::Comparison(lhs, comparison, rhs) => {
+                match (lhs.value(root, current), rhs.value(root, current)) {
+                    (Some(lhs), Some(rhs)) => comparison.test(&lhs, &rhs),
+                    // an absent value is only equal to another absent value
+                    (None, None) => matches!(comparison, Comparison::Eq | Comparison::Le | Comparison::Ge),
+                    _ => *comparison == Comparison::Ne,
+                }
+            },
+            LogicalExpr::Exists(query) => !query.select(root, current.clone()).is_empty(),
+            LogicalExpr::Function(function) => match function.call(root, current) {
+                FunctionResult::Logical(result) => result,
+                FunctionResult::Value(_) => unreachable!("value functions are rejected as tests while parsing"),
+            },
+        }
+    }
+}
+
+/// A literal, query or function call used as a comparison operand or function argument
+#[derive(Clone, Debug)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+    Function(FunctionExpr),
+}
+
+impl Operand {
+    fn value<'a>(&self, root: &'a Value, current: &Node<'a>) -> Option<Cow<'a, Value>> {
+        match self {
+            Operand::Literal(value) => Some(Cow::Owned(value.clone())),
+            Operand::Query(query) => match query.select(root, current.clone()).as_slice() {
+                [node] => Some(Cow::Borrowed(node.value)),
+                _ => None,
+            },
+            Operand::Function(function) => match function.call(root, current) {
+                FunctionResult::Value(value) => value,
+                FunctionResult::Logical(_) => None,
+            },
+        }
+    }
+
+    fn nodes<'a>(&self, root: &'a Value, current: &Node<'a>) -> Vec<Node<'a>> {
+        match self {
+            Operand::Query(query) => query.select(root, current.clone()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The type of the value this operand produces, as far as function arguments are concerned
+    fn is_value(&self) -> bool {
+        match self {
+            Operand::Literal(_) => true,
+            Operand::Query(query) => query.is_singular(),
+            Operand::Function(function) => function.function.result() == FunctionType::Value,
+        }
+    }
+
+    fn is_nodes(&self) -> bool {
+        matches!(self, Operand::Query(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FunctionType {
+    Value,
+    Logical,
+    Nodes,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Function {
+    Length,
+    Count,
+    Match,
+    Search,
+    Value,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "length" => Some(Function::Length),
+            "count" => Some(Function::Count),
+            "match" => Some(Function::Match),
+            "search" => Some(Function::Search),
+            "value" => Some(Function::Value),
+            _ => None,
+        }
+    }
+
+    fn parameters(self) -> &'static [FunctionType] {
+        match self {
+            Function::Length => &[FunctionType::Value],
+            Function::Count | Function::Value => &[FunctionType::Nodes],
+            Function::Match | Function::Search => &[FunctionType::Value, FunctionType::Value],
+        }
+    }
+
+    /// The pattern argument of `match()` or `search()` compiled to match accordingly,
+    /// `None` if it is not a valid I-Regexp
+    fn regexp(self, pattern: &str) -> Option<IRegexp> {
+        match self {
+            Function::Match => IRegexp::whole(pattern),
+            _ => IRegexp::anywhere(pattern),
+        }
+    }
+
+    fn result(self) -> FunctionType {
+        match self {
+            Function::Length | Function::Count | Function::Value => FunctionType::Value,
+            Function::Match | Function::Search => FunctionType::Logical,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FunctionExpr {
+    function: Function,
+    arguments: Vec<Operand>,
+    /// The pattern of `match()` or `search()` compiled while parsing if it is a valid literal
+    regexp: Option<IRegexp>,
+}
+
+enum FunctionResult<'a> {
+    Value(Option<Cow<'a, Value>>),
+    Logical(bool),
+}
+
+impl FunctionExpr {
+    fn call<'a>(&self, root: &'a Value, current: &Node<'a>) -> FunctionResult<'a> {
+        let value = |index: usize| self.arguments[index].value(root, current);
+
+        match self.function {
+            Function::Length => {
+                let length = value(0).and_then(|value| match value.as_ref() {
+                    Value::String(string) => Some(string.chars().count()),
+                    Value::Array(array) => Some(array.len()),
+                    Value::Object(object) => Some(object.len()),
+                    _ => None,
+                });
+
+                FunctionResult::Value(length.map(|length| Cow::Owned(Value::from(length))))
+            },
+            Function::Count => {
+                let count = self.arguments[0].nodes(root, current).len();
+                FunctionResult::Value(Some(Cow::Owned(Value::from(count))))
+            },
+            Function::Match | Function::Search => {
+                let input = value(0);
+                let Some(Value::String(input)) = input.as_deref() else {
+                    return FunctionResult::Logical(false);
+                };
+
+                let found = match &self.regexp {
+                    Some(regexp) => regexp.is_match(input),
+                    None => match value(1).as_deref() {
+                        Some(Value::String(pattern)) => self.function.regexp(pattern).is_some_and(|regexp| regexp.is_match(input)),
+                        _ => false,
+                    },
+                };
+
+                FunctionResult::Logical(found)
+            },
+            Function::Value => {
+                let mut nodes = self.arguments[0].nodes(root, current);
+                let value = (nodes.len() == 1).then(|| Cow::Borrowed(nodes.swap_remove(0).value));
+
+                FunctionResult::Value(value)
+            },
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! RFC 9485 I-Regexp patterns as used by the `match()` and `search()` JSONPath functions.
+//! Patterns are validated against the I-Regexp grammar here and matched by the `regex` crate,
+//! which runs in linear time in the length of the input.
+
+use std::fmt::Write;
+
+use regex::{Regex, RegexBuilder};
+
+/// How deeply groups can be nested, deeper patterns are treated as invalid
+const MAX_DEPTH: usize = 64;
+
+/// The Unicode general categories `\p{..}` and `\P{..}` accept, surrogates (`Cs`) can't occur in strings
+const CATEGORIES: &[&str] = &[
+    "L", "Ll", "Lm", "Lo", "Lt", "Lu",
+    "M", "Mc", "Me", "Mn",
+    "N", "Nd", "Nl", "No",
+    "P", "Pc", "Pd", "Pe", "Pf", "Pi", "Po", "Ps",
+    "Z", "Zl", "Zp", "Zs",
+    "S", "Sc", "Sk", "Sm", "So",
+    "C", "Cc", "Cf", "Cn", "Co",
+];
+
+/// A compiled I-Regexp
+#[derive(Clone, Debug)]
+pub(super) struct IRegexp(Regex);
+
+struct Alternation(Vec<Vec<Piece>>);
+
+struct Piece {
+    atom: Atom,
+    min: u32,
+    max: Option<u32>,
+}
+
+enum Atom {
+    Char(char),
+    Any,
+    Category(Category),
+    Class { negated: bool, ranges: Vec<(char, char)>, categories: Vec<Category> },
+    Group(Alternation),
+}
+
+/// A `\p{..}` escape, or a `\P{..}` escape matching everything outside of the category
+struct Category {
+    complement: bool,
+    name: &'static str,
+}
+
+impl IRegexp {
+    /// A pattern that has to match the whole input, as for `match()`
+    pub(super) fn whole(pattern: &str) -> Option<Self> {
+        Self::compile(pattern, true)
+    }
+
+    /// A pattern that can match any substring of the input, as for `search()`
+    pub(super) fn anywhere(pattern: &str) -> Option<Self> {
+        Self::compile(pattern, false)
+    }
+
+    pub(super) fn is_match(&self, input: &str) -> bool {
+        self.0.is_match(input)
+    }
+
+    fn compile(pattern: &str, anchored: bool) -> Option<Self> {
+        let mut parser = Parser { chars: pattern.chars().collect(), pos: 0, depth: 0 };
+        let root = parser.alternation()?;
+
+        if parser.pos != parser.chars.len() {
+            return None;
+        }
+
+        let mut regex = String::new();
+        write_alternation(&mut regex, &root);
+
+        if anchored {
+            regex = format!(r"\A(?:{regex})\z");
+        }
+
+        // patterns that are too large to compile, e.g. deeply nested counted repetitions, never match
+        RegexBuilder::new(&regex).build().ok().map(IRegexp)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn alternation(&mut self) -> Option<Alternation> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return None;
+        }
+
+        let mut branches = vec![self.branch()?];
+
+        while self.eat('|') {
+            branches.push(self.branch()?);
+        }
+
+        self.depth -= 1;
+        Some(Alternation(branches))
+    }
+
+    fn branch(&mut self) -> Option<Vec<Piece>> {
+        let mut pieces = Vec::new();
+
+        while !matches!(self.peek(), None | Some('|' | ')')) {
+            let atom = self.atom()?;
+            let (min, max) = self.quantifier()?;
+            pieces.push(Piece { atom, min, max });
+        }
+
+        Some(pieces)
+    }
+
+    fn atom(&mut self) -> Option<Atom> {
+        match self.next()? {
+            '(' => {
+                let group = self.alternation()?;
+                self.eat(')').then_some(Atom::Group(group))
+            },
+            '.' => Some(Atom::Any),
+            '[' => self.class(),
+            '\\' if matches!(self.peek(), Some('p' | 'P')) => self.category().map(Atom::Category),
+            '\\' => self.escape().map(Atom::Char),
+            '*' | '+' | '?' | '{' | '}' | ')' | ']' => None,
+            c => Some(Atom::Char(c)),
+        }
+    }
+
+    fn escape(&mut self) -> Option<char> {
+        match self.next()? {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            c @ ('(' | ')' | '*' | '+' | '-' | '.' | '?' | '[' | '\\' | ']' | '^' | '{' | '|' | '}') => Some(c),
+            _ => None,
+        }
+    }
+
+    /// The rest of a `\p{..}` or `\P{..}` escape after the backslash
+    fn category(&mut self) -> Option<Category> {
+        let complement = self.next()? == 'P';
+        if !self.eat('{') {
+            return None;
+        }
+
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        let name = CATEGORIES.iter().find(|category| **category == name)?;
+        self.eat('}').then_some(Category { complement, name })
+    }
+
+    fn class(&mut self) -> Option<Atom> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        let mut categories = Vec::new();
+
+        if self.eat('-') {
+            ranges.push(('-', '-'));
+        }
+
+        while !self.eat(']') {
+            if self.peek() == Some('-') {
+                // a `-` is only allowed literally right before the closing bracket
+                self.pos += 1;
+                if self.eat(']') {
+                    ranges.push(('-', '-'));
+                    break;
+                }
+                return None;
+            }
+
+            // category escapes can't be the bounds of a range
+            if self.peek() == Some('\\') && matches!(self.chars.get(self.pos + 1), Some('p' | 'P')) {
+                self.pos += 1;
+                categories.push(self.category()?);
+                continue;
+            }
+
+            let start = self.class_char()?;
+            let end = if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                self.class_char()?
+            } else {
+                start
+            };
+
+            if start > end {
+                return None;
+            }
+            ranges.push((start, end));
+        }
+
+        (!ranges.is_empty() || !categories.is_empty()).then_some(Atom::Class { negated, ranges, categories })
+    }
+
+    fn class_char(&mut self) -> Option<char> {
+        match self.next()? {
+            '\\' => self.escape(),
+            '[' | ']' | '-' => None,
+            c => Some(c),
+        }
+    }
+
+    fn quantifier(&mut self) -> Option<(u32, Option<u32>)> {
+        match self.peek() {
+            Some('*') => { self.pos += 1; Some((0, None)) },
+            Some('+') => { self.pos += 1; Some((1, None)) },
+            Some('?') => { self.pos += 1; Some((0, Some(1))) },
+            Some('{') => {
+                self.pos += 1;
+                let min = self.number()?;
+                let max = if self.eat(',') {
+                    if self.peek() == Some('}') { None } else { Some(self.number()?) }
+                } else {
+                    Some(min)
+                };
+
+                (self.eat('}') && max.is_none_or(|max| min <= max)).then_some((min, max))
+            },
+            _ => Some((1, Some(1))),
+        }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect::<String>().parse().ok()
+    }
+}
+
+// the translation spells out every character as `\x{..}`, so nothing in the pattern
+// can take on a meaning in `regex` syntax it doesn't have in I-Regexp
+
+fn write_alternation(regex: &mut String, alternation: &Alternation) {
+    for (i, branch) in alternation.0.iter().enumerate() {
+        if i > 0 {
+            regex.push('|');
+        }
+
+        for piece in branch {
+            write_piece(regex, piece);
+        }
+    }
+}
+
+fn write_piece(regex: &mut String, piece: &Piece) {
+    match &piece.atom {
+        Atom::Char(c) => write_char(regex, *c),
+        // `.` matches anything but line breaks in I-Regexp
+        Atom::Any => regex.push_str(r"[^\n\r]"),
+        Atom::Category(category) => write_category(regex, category),
+        Atom::Class { negated, ranges, categories } => {
+            regex.push_str(if *negated { "[^" } else { "[" });
+            for &(start, end) in ranges {
+                write_char(regex, start);
+                regex.push('-');
+                write_char(regex, end);
+            }
+            for category in categories {
+                write_category(regex, category);
+            }
+            regex.push(']');
+        },
+        Atom::Group(alternation) => {
+            regex.push_str("(?:");
+            write_alternation(regex, alternation);
+            regex.push(')');
+        },
+    }
+
+    match (piece.min, piece.max) {
+        (1, Some(1)) => {},
+        (min, Some(max)) => write!(regex, "{{{min},{max}}}").expect("writing to a string cannot fail"),
+        (min, None) => write!(regex, "{{{min},}}").expect("writing to a string cannot fail"),
+    }
+}
+
+fn write_char(regex: &mut String, c: char) {
+    write!(regex, r"\x{{{:x}}}", c as u32).expect("writing to a string cannot fail");
+}
+
+// category names are taken from `CATEGORIES`, which `regex` knows by the same abbreviations
+fn write_category(regex: &mut String, category: &Category) {
+    let escape = if category.complement { 'P' } else { 'p' };
+    write!(regex, r"\{escape}{{{}}}", category.name).expect("writing to a string cannot fail");
+}
@@ -0,0 +1,564 @@
+use serde_json::Value;
+
+use super::{Function, FunctionExpr, FunctionType, LogicalExpr, Operand, Query, Segment, Selector};
+use crate::filter::Comparison;
+use crate::JsonNavError;
+
+/// The largest magnitude of an index or slice bound, I-JSON's exactly representable integers
+const MAX_INT: i64 = (1 << 53) - 1;
+
+/// How deeply filter expressions can be nested through parentheses, queries and function arguments
+const MAX_DEPTH: usize = 32;
+
+pub(super) fn parse(input: &str) -> Result<Query, JsonNavError> {
+    let mut parser = Parser { input, pos: 0, depth: 0 };
+
+    if !parser.eat("$") {
+        return Err(parser.error("expected `$`"));
+    }
+
+    let query = Query { absolute: true, segments: parser.segments()? };
+
+    if parser.pos < input.len() {
+        return Err(parser.error("unexpected character"));
+    }
+
+    Ok(query)
+}
+
+/// A parsed filter expression that may still be a bare operand,
+/// which is only valid as a function argument
+enum Expr {
+    Logical(LogicalExpr),
+    Operand(Operand, usize),
+}
+
+struct Parser<'q> {
+    input: &'q str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'q> Parser<'q> {
+    fn error(&self, message: &'static str) -> JsonNavError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, position: usize, message: &'static str) -> JsonNavError {
+        JsonNavError::Syntax { input: self.input.to_owned(), position, message }
+    }
+
+    fn rest(&self) -> &'q str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str, message: &'static str) -> Result<(), JsonNavError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, JsonNavError> {
+        let mut segments = Vec::new();
+
+        loop {
+            let before_blank = self.pos;
+            self.skip_blank();
+
+            if matches!(self.peek(), Some('.' | '[')) {
+                segments.push(self.segment()?);
+            } else {
+                self.pos = before_blank;
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn segment(&mut self) -> Result<Segment, JsonNavError> {
+        let start = self.pos;
+
+        let (descendant, selectors) = if self.eat("..") {
+            let selectors = match self.peek() {
+                Some('[') => self.bracketed_selection()?,
+                Some('*') => { self.pos += 1; vec![Selector::Wildcard] },
+                Some(c) if is_name_first(c) => vec![Selector::Name(self.member_name())],
+                _ => return Err(self.error("expected a selector after `..`")),
+            };
+            (true, selectors)
+        } else if self.eat(".") {
+            let selector = match self.peek() {
+                Some('*') => { self.pos += 1; Selector::Wildcard },
+                Some(c) if is_name_first(c) => Selector::Name(self.member_name()),
+                _ => return Err(self.error("expected a member name or `*` after `.`")),
+            };
+            (false, vec![selector])
+        } else {
+            (false, self.bracketed_selection()?)
+        };
+
+        Ok(Segment { source: self.input[start..self.pos].to_owned(), descendant, selectors })
+    }
+
+    fn member_name(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| is_name_first(c) || c.is_ascii_digit()) {
+            self.next();
+        }
+
+        self.input[start..self.pos].to_owned()
+    }
+
+    fn bracketed_selection(&mut self) -> Result<Vec<Selector>, JsonNavError> {
+        self.expect("[", "expected `[`")?;
+        self.skip_blank();
+
+        let mut selectors = vec![self.selector()?];
+        self.skip_blank();
+
+        while self.eat(",") {
+            self.skip_blank();
+            selectors.push(self.selector()?);
+            self.skip_blank();
+        }
+
+        self.expect("]", "expected `,` or `]`")?;
+        Ok(selectors)
+    }
+
+    fn selector(&mut self) -> Result<Selector, JsonNavError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string_literal()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            },
+            Some('?') => {
+                self.pos += 1;
+                self.skip_blank();
+                Ok(Selector::Filter(self.logical_expr()?))
+            },
+            Some(':' | '-' | '0'..='9') => self.index_or_slice(),
+            _ => Err(self.error("expected a selector")),
+        }
+    }
+
+    fn index_or_slice(&mut self) -> Result<Selector, JsonNavError> {
+        let start = self.optional_int()?;
+        let after_start = self.pos;
+        self.skip_blank();
+
+        if !self.eat(":") {
+            self.pos = after_start;
+            return start.map(Selector::Index).ok_or_else(|| self.error("expected an index or slice"));
+        }
+
+        self.skip_blank();
+        let end = self.optional_int()?;
+        self.skip_blank();
+
+        let step = if self.eat(":") {
+            self.skip_blank();
+            self.optional_int()?
+        } else {
+            None
+        };
+
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    fn optional_int(&mut self) -> Result<Option<i64>, JsonNavError> {
+        if matches!(self.peek(), Some('-' | '0'..='9')) {
+            self.int().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, JsonNavError> {
+        let start = self.pos;
+        let negative = self.eat("-");
+
+        match self.next() {
+            Some('0') if negative => return Err(self.error_at(start, "`-0` is not a valid integer")),
+            Some('0') if self.peek().is_some_and(|c| c.is_ascii_digit()) => {
+                return Err(self.error_at(start, "integers must not have leading zeros"));
+            },
+            Some('0'..='9') => {},
+            _ => return Err(self.error_at(start, "expected an integer")),
+        }
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.input[start..self.pos]
+            .parse()
+            .ok()
+            .filter(|int: &i64| (-MAX_INT..=MAX_INT).contains(int))
+            .ok_or_else(|| self.error_at(start, "integer out of range"))
+    }
+
+    fn string_literal(&mut self) -> Result<String, JsonNavError> {
+        let quote = self.next().expect("string literals start with a quote");
+        let mut string = String::new();
+
+        loop {
+            let position = self.pos;
+
+            match self.next() {
+                None => return Err(self.error("unterminated string literal")),
+                Some(c) if c == quote => return Ok(string),
+                Some('\\') => {
+                    let unescaped = match self.next() {
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some(c @ ('/' | '\\')) => c,
+                        Some(c) if c == quote => c,
+                        Some('u') => self.unicode_escape(position)?,
+                        _ => return Err(self.error_at(position, "invalid escape sequence")),
+                    };
+                    string.push(unescaped);
+                },
+                Some(c) if c < ' ' => return Err(self.error_at(position, "unescaped control character")),
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, position: usize) -> Result<char, JsonNavError> {
+        let high = self.hex4().ok_or_else(|| self.error_at(position, "invalid unicode escape"))?;
+
+        let code = match high {
+            0xD800..=0xDBFF => {
+                let low = self.eat("\\u")
+                    .then(|| self.hex4())
+                    .flatten()
+                    .filter(|low| (0xDC00..=0xDFFF).contains(low))
+                    .ok_or_else(|| self.error_at(position, "unpaired surrogate"))?;
+
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            },
+            0xDC00..=0xDFFF => return Err(self.error_at(position, "unpaired surrogate")),
+            code => code,
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error_at(position, "invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.rest().get(..4)?;
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        self.pos += 4;
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    fn logical_expr(&mut self) -> Result<LogicalExpr, JsonNavError> {
+        let expr = self.or_expr()?;
+        self.to_logical(expr)
+    }
+
+    fn to_logical(&self, expr: Expr) -> Result<LogicalExpr, JsonNavError> {
+        match expr {
+            Expr::Logical(logical) => Ok(logical),
+            Expr::Operand(Operand::Query(query), _) => Ok(LogicalExpr::Exists(query)),
+            Expr::Operand(Operand::Function(function), position) => match function.function.result() {
+                FunctionType::Value => Err(self.error_at(position, "the result of this function must be compared")),
+                _ => Ok(LogicalExpr::Function(function)),
+            },
+            Expr::Operand(_, position) => Err(self.error_at(position, "literals must be compared")),
+        }
+    }
+
+    // every kind of nesting passes through here, so this is where the recursion is bounded
+    fn or_expr(&mut self) -> Result<Expr, JsonNavError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+
+        self.depth += 1;
+        let expr = self.disjunction();
+        self.depth -= 1;
+        expr
+    }
+
+    fn disjunction(&mut self) -> Result<Expr, JsonNavError> {
+        let first = self.and_expr()?;
+        let mut exprs = Vec::new();
+
+        loop {
+            let before_blank = self.pos;
+            self.skip_blank();
+
+            if !self.eat("||") {
+                self.pos = before_blank;
+                break;
+            }
+
+            self.skip_blank();
+            let expr = self.and_expr()?;
+            exprs.push(self.to_logical(expr)?);
+        }
+
+        if exprs.is_empty() {
+            return Ok(first);
+        }
+
+        exprs.insert(0, self.to_logical(first)?);
+        Ok(Expr::Logical(LogicalExpr::Or(exprs)))
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, JsonNavError> {
+        let first = self.basic_expr()?;
+        let mut exprs = Vec::new();
+
+        loop {
+            let before_blank = self.pos;
+            self.skip_blank();
+
+            if !self.eat("&&") {
+                self.pos = before_blank;
+                break;
+            }
+
+            self.skip_blank();
+            let expr = self.basic_expr()?;
+            exprs.push(self.to_logical(expr)?);
+        }
+
+        if exprs.is_empty() {
+            return Ok(first);
+        }
+
+        exprs.insert(0, self.to_logical(first)?);
+        Ok(Expr::Logical(LogicalExpr::And(exprs)))
+    }
+
+    fn basic_expr(&mut self) -> Result<Expr, JsonNavError> {
+        let start = self.pos;
+
+        if self.eat("!") {
+            self.skip_blank();
+
+            let negated = if self.peek() == Some('(') {
+                self.paren_expr()?
+            } else {
+                let position = self.pos;
+                match self.operand()? {
+                    operand @ (Operand::Query(_) | Operand::Function(_)) => self.to_logical(Expr::Operand(operand, position))?,
+                    _ => return Err(self.error_at(start, "only queries and logical functions can be negated")),
+                }
+            };
+
+            return Ok(Expr::Logical(LogicalExpr::Not(Box::new(negated))));
+        }
+
+        if self.peek() == Some('(') {
+            return Ok(Expr::Logical(self.paren_expr()?));
+        }
+
+        let lhs = self.operand()?;
+        let before_blank = self.pos;
+        self.skip_blank();
+
+        let Some(comparison) = self.comparison_op() else {
+            self.pos = before_blank;
+            return Ok(Expr::Operand(lhs, start));
+        };
+
+        self.skip_blank();
+        let rhs_start = self.pos;
+        let rhs = self.operand()?;
+
+        self.check_comparable(&lhs, start)?;
+        self.check_comparable(&rhs, rhs_start)?;
+
+        Ok(Expr::Logical(LogicalExpr::Comparison(lhs, comparison, rhs)))
+    }
+
+    fn paren_expr(&mut self) -> Result<LogicalExpr, JsonNavError> {
+        self.expect("(", "expected `(`")?;
+        self.skip_blank();
+        let expr = self.logical_expr()?;
+        self.skip_blank();
+        self.expect(")", "expected `)`")?;
+        Ok(expr)
+    }
+
+    fn comparison_op(&mut self) -> Option<Comparison> {
+        [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+            .into_iter()
+            .find_map(|(token, comparison)| self.eat(token).then_some(comparison))
+    }
+
+    fn check_comparable(&self, operand: &Operand, position: usize) -> Result<(), JsonNavError> {
+        match operand {
+            Operand::Query(query) if !query.is_singular() => {
+                Err(self.error_at(position, "only singular queries can be compared"))
+            },
+            Operand::Function(function) if function.function.result() != FunctionType::Value => {
+                Err(self.error_at(position, "the result of this function cannot be compared"))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, JsonNavError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Operand::Literal(Value::String(self.string_literal()?))),
+            Some('-' | '0'..='9') => self.number().map(Operand::Literal),
+            Some('$' | '@') => {
+                let absolute = self.next() == Some('$');
+                Ok(Operand::Query(Query { absolute, segments: self.segments()? }))
+            },
+            Some('a'..='z') => {
+                let start = self.pos;
+                while matches!(self.peek(), Some('a'..='z' | '0'..='9' | '_')) {
+                    self.pos += 1;
+                }
+
+                match &self.input[start..self.pos] {
+                    name if self.peek() == Some('(') => self.function(name, start).map(Operand::Function),
+                    "true" => Ok(Operand::Literal(Value::Bool(true))),
+                    "false" => Ok(Operand::Literal(Value::Bool(false))),
+                    "null" => Ok(Operand::Literal(Value::Null)),
+                    _ => Err(self.error_at(start, "unknown identifier")),
+                }
+            },
+            _ => Err(self.error("expected a literal, query or function")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, JsonNavError> {
+        let start = self.pos;
+        self.eat("-");
+
+        match self.next() {
+            Some('0') => {},
+            Some('1'..='9') => self.skip_digits(),
+            _ => return Err(self.error_at(start, "expected a number")),
+        }
+
+        if self.eat(".") {
+            self.expect_digits(start)?;
+        }
+
+        if self.eat("e") || self.eat("E") {
+            let _ = self.eat("+") || self.eat("-");
+            self.expect_digits(start)?;
+        }
+
+        serde_json::from_str(&self.input[start..self.pos])
+            .map_err(|_| self.error_at(start, "number out of range"))
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_digits(&mut self, start: usize) -> Result<(), JsonNavError> {
+        if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return Err(self.error_at(start, "expected a number"));
+        }
+
+        self.skip_digits();
+        Ok(())
+    }
+
+    fn function(&mut self, name: &str, start: usize) -> Result<FunctionExpr, JsonNavError> {
+        let function = Function::from_name(name).ok_or_else(|| self.error_at(start, "unknown function"))?;
+
+        self.expect("(", "expected `(`")?;
+        self.skip_blank();
+
+        let mut arguments = Vec::new();
+
+        if !self.eat(")") {
+            loop {
+                arguments.push(self.argument()?);
+                self.skip_blank();
+
+                if self.eat(")") {
+                    break;
+                }
+
+                self.expect(",", "expected `,` or `)`")?;
+                self.skip_blank();
+            }
+        }
+
+        let parameters = function.parameters();
+        let well_typed = arguments.len() == parameters.len()
+            && arguments.iter().zip(parameters).all(|(argument, parameter)| match parameter {
+                FunctionType::Value => argument.is_value(),
+                FunctionType::Nodes => argument.is_nodes(),
+                FunctionType::Logical => false,
+            });
+
+        if !well_typed {
+            return Err(self.error_at(start, "invalid function arguments"));
+        }
+
+        let regexp = match (function, arguments.get(1)) {
+            (Function::Match | Function::Search, Some(Operand::Literal(Value::String(pattern)))) => function.regexp(pattern),
+            _ => None,
+        };
+
+        Ok(FunctionExpr { function, arguments, regexp })
+    }
+
+    fn argument(&mut self) -> Result<Operand, JsonNavError> {
+        let start = self.pos;
+
+        match self.or_expr()? {
+            Expr::Operand(operand, _) => Ok(operand),
+            // none of the standard functions take a logical argument
+            Expr::Logical(_) => Err(self.error_at(start, "invalid function arguments")),
+        }
+    }
+}
+
+fn is_name_first(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c >= '\u{80}'
+}
@@ -1,16 +1,195 @@
 use thiserror::Error;
 
+mod convert;
+#[cfg(feature = "serde_json")]
+mod de;
+mod filter;
+#[cfg(feature = "serde_json")]
+mod jsonpath;
+mod navigable;
+mod path;
+#[cfg(feature = "serde_json")]
+mod pointer;
+#[cfg(feature = "chrono")]
+mod temporal;
+
+/// INTERNAL
+/// Runtime support for the code generated by `json_nav!`
+#[doc(hidden)]
+pub mod internal;
+
+pub use convert::{FloatConversion, FromJsonNav, Overflow, Policy};
+#[cfg(feature = "serde_json")]
+pub use jsonpath::JsonPath;
+pub use navigable::Navigable;
+pub use path::{NavPath, PathSegment, PathStyle, Segment, SegmentRef};
+#[cfg(feature = "serde_json")]
+pub use pointer::JsonPointer;
+#[cfg(feature = "chrono")]
+pub use temporal::TimeFormat;
+
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum JsonNavError {
     #[error("could not navigate to {path}")]
     Navigation {
-        path: &'static str
+        path: NavPath
+    },
+
+    #[error("index {index} is out of bounds for {path} of length {len}")]
+    OutOfBounds {
+        path: NavPath,
+        index: i128,
+        len: usize,
+    },
+
+    #[error("the predicate {predicate} filtered out all {candidates} candidates at {path}")]
+    Filtered {
+        path: NavPath,
+        predicate: &'static str,
+        candidates: usize,
+    },
+
+    #[error("no value matches {segment} at {path}")]
+    NoMatch {
+        path: NavPath,
+        segment: String,
+    },
+
+    #[error("invalid syntax at position {position} of {input}: {message}")]
+    Syntax {
+        input: String,
+        position: usize,
+        message: &'static str,
+    },
+
+    /// `value` is the number as it was found, before any rounding
+    #[error("{value} is out of range for {target}, expected {min}..={max}")]
+    OutOfRange {
+        value: String,
+        target: &'static str,
+        min: i128,
+        max: u128,
     },
 
     #[error("type mismatch, expected {expected}")]
     TypeMismatch {
         expected: &'static str,
     },
+
+    /// `value` is the number as it was found, before any rounding
+    #[error("{value} cannot be converted to {target}, {reason}")]
+    InvalidNumber {
+        value: String,
+        target: &'static str,
+        reason: &'static str,
+    },
+
+    /// `value` is the string or number that was found, `expected` describes the format it should have followed
+    #[error("could not parse {value} at {path}, expected {expected}")]
+    InvalidTime {
+        path: NavPath,
+        value: String,
+        expected: String,
+    },
+
+    /// `value` is the string that was found, `allowed` the strings of an `; as enum { .. }` clause
+    #[error("{value:?} at {path} is not one of {}", display_allowed(.allowed))]
+    UnknownVariant {
+        path: NavPath,
+        value: String,
+        allowed: Vec<&'static str>,
+    },
+
+    /// A string that was converted leniently does not hold a `target`
+    #[error("could not parse {input:?} as {target}")]
+    Parse {
+        input: String,
+        target: &'static str,
+    },
+
+    /// `field` is the location of the value that failed to deserialize, relative to `path`
+    #[error("could not deserialize {path}, {message} at {}", display_field(.path, .field))]
+    Deserialize {
+        path: NavPath,
+        field: Vec<PathSegment>,
+        message: String,
+    },
+
+    #[error("cannot write into {path}, it is not an {expected}")]
+    Blocked {
+        path: NavPath,
+        expected: &'static str,
+    },
+
+    #[error("none of the alternatives matched ({})", display_attempts(.attempts))]
+    NoAlternative {
+        attempts: Vec<JsonNavError>,
+    },
+}
+
+impl JsonNavError {
+    /// Changes how the failing location of this error is rendered, e.g. as a JSON Pointer
+    pub fn with_path_style(self, style: PathStyle) -> Self {
+        match self {
+            JsonNavError::Navigation { path } => JsonNavError::Navigation { path: path.with_style(style) },
+            JsonNavError::OutOfBounds { path, index, len } => JsonNavError::OutOfBounds { path: path.with_style(style), index, len },
+            JsonNavError::Filtered { path, predicate, candidates } => JsonNavError::Filtered { path: path.with_style(style), predicate, candidates },
+            JsonNavError::NoMatch { path, segment } => JsonNavError::NoMatch { path: path.with_style(style), segment },
+            JsonNavError::Deserialize { path, field, message } => JsonNavError::Deserialize { path: path.with_style(style), field, message },
+            JsonNavError::Blocked { path, expected } => JsonNavError::Blocked { path: path.with_style(style), expected },
+            JsonNavError::InvalidTime { path, value, expected } => JsonNavError::InvalidTime { path: path.with_style(style), value, expected },
+            JsonNavError::UnknownVariant { path, value, allowed } => JsonNavError::UnknownVariant { path: path.with_style(style), value, allowed },
+            JsonNavError::NoAlternative { attempts } => JsonNavError::NoAlternative {
+                attempts: attempts.into_iter().map(|attempt| attempt.with_path_style(style)).collect(),
+            },
+            error @ (JsonNavError::Syntax { .. } | JsonNavError::TypeMismatch { .. } | JsonNavError::Parse { .. }
+                | JsonNavError::OutOfRange { .. } | JsonNavError::InvalidNumber { .. }) => error,
+        }
+    }
+
+    /// Whether this error means that the navigated path does not exist, including a filter
+    /// that kept nothing, as opposed to a value of the wrong type being found along it
+    pub fn is_missing(&self) -> bool {
+        match self {
+            JsonNavError::Navigation { .. } | JsonNavError::OutOfBounds { .. } | JsonNavError::NoMatch { .. }
+                | JsonNavError::Filtered { .. } => true,
+            JsonNavError::NoAlternative { attempts } => attempts.iter().all(JsonNavError::is_missing),
+            _ => false,
+        }
+    }
+
+    /// The location at which navigation failed, if this error has one
+    pub fn path(&self) -> Option<&NavPath> {
+        match self {
+            JsonNavError::Navigation { path }
+            | JsonNavError::OutOfBounds { path, .. }
+            | JsonNavError::Filtered { path, .. }
+            | JsonNavError::NoMatch { path, .. }
+            | JsonNavError::Deserialize { path, .. }
+            | JsonNavError::Blocked { path, .. }
+            | JsonNavError::InvalidTime { path, .. }
+            | JsonNavError::UnknownVariant { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+fn display_field(path: &NavPath, field: &[PathSegment]) -> NavPath {
+    field.iter().fold(path.clone(), |path, segment| path.join(segment.clone()))
+}
+
+fn display_allowed(allowed: &[&str]) -> String {
+    allowed.iter()
+        .map(|value| format!("{value:?}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_attempts(attempts: &[JsonNavError]) -> String {
+    attempts.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// INTERNAL
@@ -18,75 +197,651 @@ pub enum JsonNavError {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! json_nav_internal {
-    ($json:expr, $base_path:expr, $path:expr) => {
-    	$json.and_then(|x| {
-	    	x.get($path)
-	    		.ok_or($crate::JsonNavError::Navigation { path: concat!($base_path, '.', $path) })
-	    })
+    // splits the invocation into its `=>` separated segments, each in brackets, and the conversion following
+    // the top-level `;`. Every step takes a whole segment so long paths stay clear of the recursion limit
+    (@split $json:expr, [$($path:tt)*] $segment:tt => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @split $json, [$($path)* [$segment]] $($rest)+ }
     };
 
-    ($json:expr, $base_path:expr, $first_path:expr, $($path:expr),+) => {
-        let _x = $crate::json_nav_internal!{ $json, $base_path, $first_path };
-        $crate::json_nav_internal!{ _x, concat!($base_path, '.', $first_path), $($path),+ }
+    (@split $json:expr, [$($path:tt)*] $segment:tt ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @conversion $json, [$($path)* [$segment]] [] $($conversion)+ }
     };
-}
 
+    (@split $json:expr, [$($path:tt)*] $segment:tt) => {
+        $crate::json_nav_internal!{ @root $json, [$($path)* [$segment]] }
+    };
 
-#[doc = include_str!("../README.md")]
-#[macro_export]
-macro_rules! json_nav {
-    ($json:expr => $($path:expr)=>+) => {
+    (@split $json:expr, [$($path:tt)*] .. $segment:expr => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @split $json, [$($path)* [.. $segment]] $($rest)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] .. $segment:expr ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @conversion $json, [$($path)* [.. $segment]] [] $($conversion)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] .. $segment:expr) => {
+        $crate::json_nav_internal!{ @root $json, [$($path)* [.. $segment]] }
+    };
+
+    // `**` is an alias of `..`, it would otherwise parse as a double dereference
+    (@split $json:expr, [$($path:tt)*] * * $segment:expr => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @split $json, [$($path)* [.. $segment]] $($rest)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] * * $segment:expr ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @conversion $json, [$($path)* [.. $segment]] [] $($conversion)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] * * $segment:expr) => {
+        $crate::json_nav_internal!{ @root $json, [$($path)* [.. $segment]] }
+    };
+
+    (@split $json:expr, [$($path:tt)*] $segment:expr => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @split $json, [$($path)* [$segment]] $($rest)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] $segment:expr ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @conversion $json, [$($path)* [$segment]] [] $($conversion)+ }
+    };
+
+    (@split $json:expr, [$($path:tt)*] $segment:expr) => {
+        $crate::json_nav_internal!{ @root $json, [$($path)* [$segment]] }
+    };
+
+    // a trailing `?` makes the whole navigation optional
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] ?) => {
+        $crate::json_nav_internal!{ @optional $json, [$($path)*] $($conversion)* }
+    };
+
+    // `or` and `or_else` fall back to a default if the path does not exist,
+    // `or_any` and `or_else_any` also if the value has the wrong type
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] or $($default:tt)+) => {
+        $crate::json_nav_internal!{ @optional $json, [$($path)*] $($conversion)* }
+            .map(|x| x.unwrap_or($($default)+))
+    };
+
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] or_else $($default:tt)+) => {
+        $crate::json_nav_internal!{ @optional $json, [$($path)*] $($conversion)* }
+            .map(|x| x.unwrap_or_else($($default)+))
+    };
+
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] or_any $($default:tt)+) => {
+        ::core::result::Result::<_, $crate::JsonNavError>::Ok(
+            $crate::json_nav_internal!{ @single $json, [$($path)*] $($conversion)* }.unwrap_or($($default)+)
+        )
+    };
+
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] or_else_any $($default:tt)+) => {
+        ::core::result::Result::<_, $crate::JsonNavError>::Ok(
+            $crate::json_nav_internal!{ @single $json, [$($path)*] $($conversion)* }.unwrap_or_else(|_| ($($default)+)())
+        )
+    };
+
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @conversion $json, [$($path)*] [$($conversion)* $t] $($rest)* }
+    };
+
+    (@conversion $json:expr, [$($path:tt)*] [$($conversion:tt)*]) => {
+        $crate::json_nav_internal!{ @root $json, [$($path)*] $($conversion)* }
+    };
+
+    (@optional $json:expr, [$([$($segment:tt)+])+] $($conversion:tt)*) => {
+        {
+            use $crate::internal::Root as _;
+            let _x = ::core::result::Result::Ok(($json).json_nav_root(stringify!($json)));
+            $( let _x = $crate::json_nav_internal!{ @segment (_x) $($segment)+ }; )+
+            $crate::internal::optional(_x).and_then(|x| {
+                x.map(|x| $crate::internal::Selection::map(x, $crate::json_nav_internal!{ @convert $($conversion)* })).transpose()
+            })
+        }
+    };
+
+    // `@root` for navigations that have to select a single value, the ones `or_any` and `or_else_any` apply to
+    (@single $json:expr, [$([$($segment:tt)+])+] $($conversion:tt)*) => {
         {
-    		$crate::json_nav_internal!{ Ok(&$json), stringify!($json), $($path),+ }
-    	}
+            use $crate::internal::Root as _;
+            let _x = ::core::result::Result::Ok(($json).json_nav_root(stringify!($json)));
+            $( let _x = $crate::json_nav_internal!{ @segment (_x) $($segment)+ }; )+
+            $crate::internal::single(_x).and_then(|x| $crate::internal::Selection::map(x, $crate::json_nav_internal!{ @convert $($conversion)* }))
+        }
+    };
+
+    (@root $json:expr, [$([$($segment:tt)+])+] $($conversion:tt)*) => {
+        {
+            use $crate::internal::Root as _;
+            let _x = ::core::result::Result::Ok(($json).json_nav_root(stringify!($json)));
+            $( let _x = $crate::json_nav_internal!{ @segment (_x) $($segment)+ }; )+
+            _x.and_then(|x| $crate::internal::Selection::map(x, $crate::json_nav_internal!{ @convert $($conversion)* }))
+        }
+    };
+
+    // splits the path of a filter predicate or an alternative into its `=>` separated segments
+    (@path ($x:expr) [$($segment:tt)+] => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @path ($crate::json_nav_internal!{ @segment ($x) $($segment)+ }) [] $($rest)+ }
+    };
+
+    (@path ($x:expr) [$($segment:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @path ($x) [$($segment)* $t] $($rest)* }
+    };
+
+    (@path ($x:expr) [$($segment:tt)+]) => {
+        $crate::json_nav_internal!{ @segment ($x) $($segment)+ }
+    };
+
+    (@path ($x:expr) []) => {
+        $x
+    };
+
+    (@segment ($x:expr) ( $($alternatives:tt)* )) => {
+        $crate::json_nav_internal!{ @alternatives ($x) [] [] $($alternatives)* }
+    };
+
+    (@segment ($x:expr) *) => {
+        ($x).and_then($crate::internal::Selection::wildcard)
+    };
+
+    (@segment ($x:expr) [first]) => {
+        ($x).and_then(|x| $crate::internal::Selection::get(x, &0isize))
+    };
+
+    (@segment ($x:expr) [last]) => {
+        ($x).and_then(|x| $crate::internal::Selection::get(x, &-1isize))
+    };
+
+    (@segment ($x:expr) [ ? ( $($predicate:tt)+ ) ]) => {
+        ($x).and_then(|x| $crate::internal::Selection::filter(x, stringify!($($predicate)+), |x| {
+            $crate::json_nav_internal!{ @predicate (x) [] $($predicate)+ }
+        }))
+    };
+
+    (@segment ($x:expr) [ $selector:expr ]) => {
+        ($x).and_then(|x| $crate::internal::Selection::index(x, &($selector)))
+    };
+
+    (@segment ($x:expr) .. $segment:expr) => {
+        ($x).and_then(|x| $crate::internal::Selection::descendants(x, &$segment))
+    };
+
+    (@segment ($x:expr) * * $segment:expr) => {
+        ($x).and_then(|x| $crate::internal::Selection::descendants(x, &$segment))
+    };
+
+    (@segment ($x:expr) $segment:expr) => {
+        ($x).and_then(|x| $crate::internal::Selection::get(x, &$segment))
+    };
+
+    // splits a filter predicate into the sub-path and the comparison, if any
+    (@predicate ($x:expr) [$($path:tt)+] == $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Eq,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
+    };
+
+    (@predicate ($x:expr) [$($path:tt)+] != $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Ne,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
+    };
+
+    (@predicate ($x:expr) [$($path:tt)+] < $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Lt,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
+    };
+
+    (@predicate ($x:expr) [$($path:tt)+] <= $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Le,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
     };
 
-    ($json:expr => $($path:expr)=>+; as object) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_object().ok_or($crate::JsonNavError::TypeMismatch { expected: "object" }))
-    	}
+    (@predicate ($x:expr) [$($path:tt)+] > $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Gt,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
     };
 
-    ($json:expr => $($path:expr)=>+; as array) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_array().ok_or($crate::JsonNavError::TypeMismatch { expected: "array" }))
-    	}
+    (@predicate ($x:expr) [$($path:tt)+] >= $($value:tt)+) => {
+        $crate::internal::test_comparison(
+            $crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ },
+            $crate::internal::Comparison::Ge,
+            &$crate::json_nav_internal!{ @literal $($value)+ },
+        )
     };
 
-    ($json:expr => $($path:expr)=>+; as str) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_str().ok_or($crate::JsonNavError::TypeMismatch{ expected: "str" }))
-    	}
+    (@predicate ($x:expr) [$($path:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @predicate ($x) [$($path)* $t] $($rest)* }
     };
 
-    ($json:expr => $($path:expr)=>+; as bool) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_bool().ok_or($crate::JsonNavError::TypeMismatch{ expected: "bool" }))
-    	}
+    (@predicate ($x:expr) [$($path:tt)+]) => {
+        $crate::internal::test_existence($crate::json_nav_internal!{ @path (::core::result::Result::Ok($x)) [] $($path)+ })
     };
 
-    ($json:expr => $($path:expr)=>+; as u64) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_u64().ok_or($crate::JsonNavError::TypeMismatch{ expected: "u64" }))
-    	}
+    // the value a predicate compares to, `null` is not a rust expression
+    (@literal null) => {
+        $crate::internal::Literal::Null
     };
 
-    ($json:expr => $($path:expr)=>+; as i64) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_i64().ok_or($crate::JsonNavError::TypeMismatch{ expected: "i64" }))
-    	}
+    (@literal $($value:tt)+) => {
+        $crate::internal::Literal::from($($value)+)
     };
 
-    ($json:expr => $($path:expr)=>+; as f64) => {
-    	{
-    		let _x = json_nav!{ $json => $($path)=>+ };
-    		_x.and_then(|x| x.as_f64().ok_or($crate::JsonNavError::TypeMismatch{ expected: "f64" }))
-    	}
+    // splits a parenthesized segment into its `|` separated alternative paths
+    (@alternatives ($x:expr) [$($alternatives:tt)*] [$($path:tt)*] | $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @alternatives ($x) [$($alternatives)* [$($path)*]] [] $($rest)* }
+    };
+
+    (@alternatives ($x:expr) [$($alternatives:tt)*] [$($path:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @alternatives ($x) [$($alternatives)*] [$($path)* $t] $($rest)* }
+    };
+
+    (@alternatives ($x:expr) [] [$($path:tt)*]) => {
+        $crate::json_nav_internal!{ @path ($x) [] $($path)* }
+    };
+
+    (@alternatives ($x:expr) [$([$($alternative:tt)*])+] [$($path:tt)*]) => {
+        ($x).and_then(|x| $crate::internal::Selection::first_of(x, [
+            $( &|x| $crate::json_nav_internal!{ @path (::core::result::Result::Ok(x)) [] $($alternative)* }, )+
+            &|x| $crate::json_nav_internal!{ @path (::core::result::Result::Ok(x)) [] $($path)* },
+        ]))
+    };
+
+    // the counterparts of `@root`, `@path`, `@segment` and `@convert` for `json_nav_mut!`,
+    // which only supports segments selecting a single value
+    (@split_mut $json:expr, [$($path:tt)*] ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @root_mut $json, [$($path)*] $($conversion)+ }
+    };
+
+    (@split_mut $json:expr, [$($path:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @split_mut $json, [$($path)* $t] $($rest)* }
+    };
+
+    (@split_mut $json:expr, [$($path:tt)*]) => {
+        $crate::json_nav_internal!{ @root_mut $json, [$($path)*] }
+    };
+
+    (@root_mut $json:expr, [$($path:tt)+] $($conversion:tt)*) => {
+        {
+            let _x = ::core::result::Result::Ok($crate::internal::CursorMut::root(&mut $json, stringify!($json)));
+            let _x = $crate::json_nav_internal!{ @path_mut get (_x) [] $($path)+ };
+            _x.and_then($crate::json_nav_internal!{ @convert_mut $($conversion)* })
+        }
+    };
+
+    // `$method` is the `CursorMut` method taking a segment, `get` or `entry`
+    (@path_mut $method:ident ($x:expr) [$($segment:tt)+] => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @path_mut $method ($crate::json_nav_internal!{ @segment_mut $method ($x) $($segment)+ }) [] $($rest)+ }
+    };
+
+    (@path_mut $method:ident ($x:expr) [$($segment:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @path_mut $method ($x) [$($segment)* $t] $($rest)* }
+    };
+
+    (@path_mut $method:ident ($x:expr) [$($segment:tt)+]) => {
+        $crate::json_nav_internal!{ @segment_mut $method ($x) $($segment)+ }
+    };
+
+    (@segment_mut $method:ident ($x:expr) [first]) => {
+        ($x).and_then(|x| $crate::internal::CursorMut::$method(x, &0isize))
+    };
+
+    (@segment_mut $method:ident ($x:expr) [last]) => {
+        ($x).and_then(|x| $crate::internal::CursorMut::$method(x, &-1isize))
+    };
+
+    (@segment_mut $method:ident ($x:expr) $segment:expr) => {
+        ($x).and_then(|x| $crate::internal::CursorMut::$method(x, &$segment))
+    };
+
+    // splits a `json_set!` invocation into the path and the value following the `=`
+    (@split_set $json:expr, [$($path:tt)*] = $value:expr) => {
+        {
+            let _x = ::core::result::Result::Ok($crate::internal::CursorMut::root(&mut $json, stringify!($json)));
+            let _x = $crate::json_nav_internal!{ @path_mut entry (_x) [] $($path)+ };
+            _x.map(|x| {
+                let x = $crate::internal::CursorMut::into_value(x);
+                *x = $crate::internal::Value::from($value);
+                x
+            })
+        }
+    };
+
+    (@split_set $json:expr, [$($path:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @split_set $json, [$($path)* $t] $($rest)* }
+    };
+
+    // splits a `json_take!` or `json_remove!` invocation into the conversion, the parent segments and the last segment,
+    // `$method` is the `CursorMut` method removing the last segment from its parent
+    (@split_take $method:ident $json:expr, [$($path:tt)*] ; $($conversion:tt)+) => {
+        $crate::json_nav_internal!{ @take $method $json, [$($conversion)+] [] [] $($path)* }
+    };
+
+    (@split_take $method:ident $json:expr, [$($path:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @split_take $method $json, [$($path)* $t] $($rest)* }
+    };
+
+    (@split_take $method:ident $json:expr, [$($path:tt)*]) => {
+        $crate::json_nav_internal!{ @take $method $json, [] [] [] $($path)* }
+    };
+
+    (@take $method:ident $json:expr, [$($conversion:tt)*] [$($parents:tt)*] [$($segment:tt)+] => $($rest:tt)+) => {
+        $crate::json_nav_internal!{ @take $method $json, [$($conversion)*] [$($parents)* [$($segment)+]] [] $($rest)+ }
+    };
+
+    (@take $method:ident $json:expr, [$($conversion:tt)*] [$($parents:tt)*] [$($segment:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::json_nav_internal!{ @take $method $json, [$($conversion)*] [$($parents)*] [$($segment)* $t] $($rest)* }
+    };
+
+    (@take $method:ident $json:expr, [$($conversion:tt)*] [$([$($parent:tt)+])*] [$($last:tt)+]) => {
+        {
+            let _x = ::core::result::Result::Ok($crate::internal::CursorMut::root(&mut $json, stringify!($json)));
+            $( let _x = $crate::json_nav_internal!{ @segment_mut get (_x) $($parent)+ }; )*
+            _x.and_then(|x| $crate::internal::CursorMut::$method(
+                x,
+                &$crate::json_nav_internal!{ @last_segment $($last)+ },
+                $crate::json_nav_internal!{ @convert_owned $($conversion)* },
+            ))
+        }
+    };
+
+    (@last_segment [first]) => {
+        0isize
+    };
+
+    (@last_segment [last]) => {
+        -1isize
+    };
+
+    (@last_segment $segment:expr) => {
+        $segment
+    };
+
+    // the expected type and a conversion of an owned value handing it back if it has the wrong type
+    (@convert_owned) => {
+        ("value", |x: $crate::internal::Value| ::core::result::Result::<_, $crate::internal::Value>::Ok(x))
+    };
+
+    (@convert_owned as object) => {
+        ("object", |x: $crate::internal::Value| match x {
+            $crate::internal::Value::Object(object) => ::core::result::Result::Ok(object),
+            x => ::core::result::Result::Err(x),
+        })
+    };
+
+    (@convert_owned as array) => {
+        ("array", |x: $crate::internal::Value| match x {
+            $crate::internal::Value::Array(array) => ::core::result::Result::Ok(array),
+            x => ::core::result::Result::Err(x),
+        })
+    };
+
+    (@convert_owned as str) => {
+        ("str", |x: $crate::internal::Value| match x {
+            $crate::internal::Value::String(string) => ::core::result::Result::Ok(string),
+            x => ::core::result::Result::Err(x),
+        })
+    };
+
+    (@convert_owned as bool) => {
+        ("bool", |x: $crate::internal::Value| x.as_bool().ok_or(x))
+    };
+
+    (@convert_owned as u64) => {
+        ("u64", |x: $crate::internal::Value| x.as_u64().ok_or(x))
+    };
+
+    (@convert_owned as i64) => {
+        ("i64", |x: $crate::internal::Value| x.as_i64().ok_or(x))
+    };
+
+    (@convert_owned as f64) => {
+        ("f64", |x: $crate::internal::Value| x.as_f64().ok_or(x))
+    };
+
+    (@convert_mut) => {
+        |x| ::core::result::Result::Ok($crate::internal::CursorMut::into_value(x))
+    };
+
+    (@convert_mut as object) => {
+        |x| $crate::internal::CursorMut::into_value(x).as_object_mut().ok_or($crate::JsonNavError::TypeMismatch { expected: "object" })
+    };
+
+    (@convert_mut as array) => {
+        |x| $crate::internal::CursorMut::into_value(x).as_array_mut().ok_or($crate::JsonNavError::TypeMismatch { expected: "array" })
+    };
+
+    (@convert) => {
+        $crate::internal::Cursor::into_value
+    };
+
+    (@convert deserialize $t:ty) => {
+        |x| $crate::internal::deserialize::<$t>(x)
+    };
+
+    // `object`, `array`, `table` (an alias of `object`), `tagged`, `bytes` and `str` name the borrowed types they convert to
+    // `enum` maps each string to a value of any type, usually the variants of an enum
+    (@convert as enum { $($name:literal => $variant:expr),+ $(,)? }) => {
+        |x| $crate::internal::match_variant(x, [$(($name, $variant)),+])
+    };
+
+    (@convert as object) => {
+        $crate::internal::as_object
+    };
+
+    (@convert as array) => {
+        $crate::internal::as_array
+    };
+
+    (@convert as table) => {
+        $crate::internal::as_object
+    };
+
+    // `datetime` is the format's own datetime type for `toml` and a `chrono` datetime for other formats,
+    // `format <format>` picks a `TimeFormat` for the `chrono` conversions
+    (@convert as datetime) => {
+        $crate::internal::as_datetime
+    };
+
+    (@convert as datetime format $format:expr) => {
+        |x| $crate::internal::parse_time::<$crate::internal::DateTime, _>(x, $crate::TimeFormat::from($format))
+    };
+
+    (@convert as date) => {
+        |x| $crate::internal::parse_time::<$crate::internal::NaiveDate, _>(x, $crate::TimeFormat::Iso8601)
+    };
+
+    (@convert as date format $format:expr) => {
+        |x| $crate::internal::parse_time::<$crate::internal::NaiveDate, _>(x, $crate::TimeFormat::from($format))
+    };
+
+    (@convert as time) => {
+        |x| $crate::internal::parse_time::<$crate::internal::NaiveTime, _>(x, $crate::TimeFormat::Iso8601)
+    };
+
+    (@convert as time format $format:expr) => {
+        |x| $crate::internal::parse_time::<$crate::internal::NaiveTime, _>(x, $crate::TimeFormat::from($format))
+    };
+
+    (@convert as duration) => {
+        |x| $crate::internal::parse_time::<$crate::internal::TimeDelta, _>(x, $crate::TimeFormat::Iso8601)
+    };
+
+    (@convert as duration format $format:expr) => {
+        |x| $crate::internal::parse_time::<$crate::internal::TimeDelta, _>(x, $crate::TimeFormat::from($format))
+    };
+
+    (@convert as tagged) => {
+        $crate::internal::as_tagged
+    };
+
+    (@convert as bytes) => {
+        |x| $crate::internal::convert::<&[u8], _>(x)
+    };
+
+    (@convert as str) => {
+        |x| $crate::internal::convert::<&str, _>(x)
+    };
+
+    // `number_str` is the digits of a number, `decimal` a `rust_decimal::Decimal` that holds them exactly
+    (@convert as number_str) => {
+        $crate::internal::as_number_str
+    };
+
+    (@convert as decimal) => {
+        |x| $crate::internal::convert::<$crate::internal::Decimal, _>(x)
+    };
+
+    (@convert as decimal lenient) => {
+        |x| $crate::internal::convert_with::<$crate::internal::Decimal, _>(x, &$crate::Policy::LENIENT)
+    };
+
+    (@convert as decimal with $policy:expr) => {
+        |x| $crate::internal::convert_with::<$crate::internal::Decimal, _>(x, &$policy)
+    };
+
+    // `lenient` and `with <policy>` pick the policy of scalar conversions, `str` becomes a `Cow<str>`
+    (@convert as str lenient) => {
+        |x| $crate::internal::convert_with::<::std::borrow::Cow<str>, _>(x, &$crate::Policy::LENIENT)
+    };
+
+    (@convert as str with $policy:expr) => {
+        |x| $crate::internal::convert_with::<::std::borrow::Cow<str>, _>(x, &$policy)
+    };
+
+    (@convert as $t:ident lenient) => {
+        |x| $crate::internal::convert_with::<$t, _>(x, &$crate::Policy::LENIENT)
+    };
+
+    (@convert as $t:ident with $policy:expr) => {
+        |x| $crate::internal::convert_with::<$t, _>(x, &$policy)
+    };
+
+    (@convert as $t:ty) => {
+        |x| $crate::internal::convert::<$t, _>(x)
+    };
+}
+
+
+#[doc = include_str!("../README.md")]
+#[macro_export]
+macro_rules! json_nav {
+    ($json:expr => $($path:tt)+) => {
+        $crate::json_nav_internal!{ @split $json, [] $($path)+ }
+    };
+}
+
+/// The counterpart of [`json_nav!`] returning `&mut Value`, or `&mut Map`/`&mut Vec` with
+/// `; as object`/`; as array`. Only segments selecting a single value are supported
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::{json_nav_mut, JsonNavError};
+///
+/// let mut value = json!({ "payload": { "features": ["a", "b"], "meta": {} } });
+///
+/// *json_nav_mut! { value => "payload" => "features" => [last] }.unwrap() = json!("c");
+/// json_nav_mut! { value => "payload" => "features"; as array }.unwrap().push(json!("d"));
+/// json_nav_mut! { value => "payload" => "meta"; as object }.unwrap().insert("seen".into(), json!(true));
+///
+/// assert_eq!(json!({ "payload": { "features": ["a", "c", "d"], "meta": { "seen": true } } }), value);
+///
+/// let error = json_nav_mut! { value => "payload" => "failure" };
+/// assert!(matches!(error, Err(JsonNavError::Navigation { path }) if path == "value.payload.failure"));
+/// ```
+#[cfg(feature = "serde_json")]
+#[macro_export]
+macro_rules! json_nav_mut {
+    ($json:expr => $($path:tt)+) => {
+        $crate::json_nav_internal!{ @split_mut $json, [] $($path)+ }
+    };
+}
+
+/// Writes a value at a path, creating missing objects along it, or arrays if the segment is an index.
+/// Indices may refer to existing elements or append one past the end of an array.
+/// Returns a mutable reference to the written value
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::{json_set, JsonNavError};
+///
+/// let mut value = json!({ "payload": { "name": "widget" } });
+///
+/// json_set! { value => "payload" => "meta" => "tags" => 0 = "new" }.unwrap();
+/// json_set! { value => "payload" => "meta" => "tags" => 1 = "shiny" }.unwrap();
+/// json_set! { value => "payload" => "meta" => "tags" => [first] = json!("used") }.unwrap();
+///
+/// assert_eq!(json!({ "payload": { "name": "widget", "meta": { "tags": ["used", "shiny"] } } }), value);
+///
+/// let error = json_set! { value => "payload" => "name" => "first" = "w" };
+/// assert_eq!("cannot write into value.payload.name, it is not an object", error.unwrap_err().to_string());
+///
+/// // a path that fails part way creates nothing
+/// let error = json_set! { value => "payload" => "meta" => "sizes" => 3 = 1 };
+/// assert!(matches!(error, Err(JsonNavError::OutOfBounds { index: 3, len: 0, .. })));
+/// assert_eq!(json!({ "payload": { "name": "widget", "meta": { "tags": ["used", "shiny"] } } }), value);
+/// ```
+#[cfg(feature = "serde_json")]
+#[macro_export]
+macro_rules! json_set {
+    ($json:expr => $($path:tt)+) => {
+        $crate::json_nav_internal!{ @split_set $json, [] $($path)+ }
+    };
+}
+
+/// Removes the value at a path from its parent object or array and returns it owned,
+/// later elements of an array shift down. With a conversion like `; as str` the value
+/// is converted into the owned type (`String`, `Map`, `Vec`, ...), and left in place if it has the wrong type
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::{json_remove, JsonNavError};
+///
+/// let mut value = json!({ "payload": { "name": "widget", "features": ["a", "b", "c"] } });
+///
+/// assert_eq!(Ok(json!("a")), json_remove! { value => "payload" => "features" => [first] });
+/// assert_eq!(Ok(String::from("widget")), json_remove! { value => "payload" => "name"; as str });
+/// assert_eq!(json!({ "payload": { "features": ["b", "c"] } }), value);
+///
+/// let error = json_remove! { value => "payload" => "name" };
+/// assert!(matches!(error, Err(JsonNavError::Navigation { path }) if path == "value.payload.name"));
+///
+/// let error = json_remove! { value => "payload" => "features"; as object };
+/// assert_eq!(Err(JsonNavError::TypeMismatch { expected: "object" }), error);
+/// assert_eq!(json!({ "payload": { "features": ["b", "c"] } }), value);
+/// ```
+#[cfg(feature = "serde_json")]
+#[macro_export]
+macro_rules! json_remove {
+    ($json:expr => $($path:tt)+) => {
+        $crate::json_nav_internal!{ @split_take remove $json, [] $($path)+ }
+    };
+}
+
+/// Like [`json_remove!`], but leaves `null` in place of the taken value
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::json_take;
+///
+/// let mut value = json!({ "payload": { "features": ["a", "b"] } });
+///
+/// assert_eq!(Ok(vec![json!("a"), json!("b")]), json_take! { value => "payload" => "features"; as array });
+/// assert_eq!(json!({ "payload": { "features": null } }), value);
+/// ```
+#[cfg(feature = "serde_json")]
+#[macro_export]
+macro_rules! json_take {
+    ($json:expr => $($path:tt)+) => {
+        $crate::json_nav_internal!{ @split_take take $json, [] $($path)+ }
     };
 }
@@ -0,0 +1,587 @@
+use std::borrow::Cow;
+
+use crate::{PathSegment, PathStyle};
+
+/// A tree of values `json_nav!` can walk, implemented for `serde_json::Value` with the `serde_json` feature.
+/// Lookups, the array length, the children and the object and array views are required,
+/// the scalar views default to `None` and filter predicates compare through them
+///
+/// ```rust
+/// use json_nav::{json_nav, Navigable, PathSegment};
+///
+/// #[derive(Debug)]
+/// enum Tree {
+///     Leaf(String),
+///     Node(Vec<(String, Tree)>),
+/// }
+///
+/// impl Navigable for Tree {
+///     type Object = [(String, Tree)];
+///     type Array = [Tree];
+///
+///     fn get_key(&self, key: &str) -> Option<&Self> {
+///         let Tree::Node(children) = self else { return None };
+///         children.iter().find(|(name, _)| name == key).map(|(_, child)| child)
+///     }
+///
+///     fn get_index(&self, _index: usize) -> Option<&Self> {
+///         None
+///     }
+///
+///     fn array_len(&self) -> Option<usize> {
+///         None
+///     }
+///
+///     fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+///         let Tree::Node(children) = self else { return None };
+///         Some(children.iter().map(|(name, child)| (PathSegment::Key(name.clone()), child)).collect())
+///     }
+///
+///     fn as_object(&self) -> Option<&Self::Object> {
+///         let Tree::Node(children) = self else { return None };
+///         Some(children)
+///     }
+///
+///     fn as_array(&self) -> Option<&Self::Array> {
+///         None
+///     }
+///
+///     fn as_str(&self) -> Option<&str> {
+///         let Tree::Leaf(leaf) = self else { return None };
+///         Some(leaf)
+///     }
+/// }
+///
+/// let tree = Tree::Node(vec![
+///     ("src".into(), Tree::Node(vec![("lib.rs".into(), Tree::Leaf("mod a;".into()))])),
+///     ("README.md".into(), Tree::Leaf("# Tree".into())),
+/// ]);
+///
+/// assert_eq!(Ok("mod a;"), json_nav! { tree => "src" => "lib.rs"; as str });
+/// assert_eq!(Ok("# Tree"), json_nav! { tree => ("readme.md" | "README.md"); as str });
+///
+/// let error = json_nav! { tree => "src" => "main.rs" };
+/// assert_eq!("could not navigate to tree.src.main.rs", error.unwrap_err().to_string());
+/// ```
+pub trait Navigable {
+    /// How paths into this kind of tree are rendered in errors
+    const PATH_STYLE: PathStyle = PathStyle::Dotted;
+
+    /// The type `; as object` converts to
+    type Object: ?Sized;
+
+    /// The type `; as array` converts to
+    type Array: ?Sized;
+
+    /// The value stored under `key` if this is an object
+    fn get_key(&self, key: &str) -> Option<&Self>;
+
+    /// The element at `index` if this is an array
+    fn get_index(&self, index: usize) -> Option<&Self>;
+
+    /// The number of elements if this is an array
+    fn array_len(&self) -> Option<usize>;
+
+    /// The elements of an array or the entries of an object in order, `None` for scalars
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>>;
+
+    /// The value stored under an integer key if this is an object that allows them,
+    /// index segments fall back to this for values that are not arrays
+    fn get_integer_key(&self, _key: i64) -> Option<&Self> {
+        None
+    }
+
+    fn as_object(&self) -> Option<&Self::Object>;
+
+    fn as_array(&self) -> Option<&Self::Array>;
+
+    /// Whether this is a null value, for formats that have one
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        None
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        None
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        None
+    }
+
+    /// The value if it is an integer that fits an `i128`, floats are never integers here
+    fn as_i128(&self) -> Option<i128> {
+        self.as_i64().map(i128::from).or_else(|| self.as_u64().map(i128::from))
+    }
+
+    /// The value if it is an integer that fits a `u128`, floats are never integers here
+    fn as_u128(&self) -> Option<u128> {
+        self.as_u64().map(u128::from)
+    }
+
+    /// The digits of a number as they were written if the format keeps them, formatted from
+    /// the other numeric views otherwise. Floats keep their fraction or exponent, `1.0` is not `1`
+    fn as_number_str(&self) -> Option<Cow<'_, str>> {
+        let digits = self.as_i64().map(|n| n.to_string())
+            .or_else(|| self.as_u64().map(|n| n.to_string()))
+            .or_else(|| self.as_f64().filter(|n| n.is_finite()).map(|n| format!("{n:?}")))?;
+
+        Some(Cow::Owned(digits))
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Navigable for serde_json::Value {
+    type Object = serde_json::Map<String, serde_json::Value>;
+    type Array = Vec<serde_json::Value>;
+
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        self.as_object()?.get(key)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.as_array().map(Vec::len)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+        match self {
+            serde_json::Value::Array(array) => Some(array.iter()
+                .enumerate()
+                .map(|(index, value)| (PathSegment::Index(index), value))
+                .collect()),
+            serde_json::Value::Object(object) => Some(object.iter()
+                .map(|(key, value)| (PathSegment::Key(key.clone()), value))
+                .collect()),
+            _ => None,
+        }
+    }
+
+    fn as_object(&self) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        self.as_object()
+    }
+
+    fn as_array(&self) -> Option<&Vec<serde_json::Value>> {
+        self.as_array()
+    }
+
+    fn is_null(&self) -> bool {
+        self.is_null()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.as_i64()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.as_u64()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.as_f64()
+    }
+
+    // with `arbitrary_precision` integers can go beyond `i64` and `u64`, floats never parse as one
+    #[cfg(feature = "arbitrary_precision")]
+    fn as_i128(&self) -> Option<i128> {
+        let serde_json::Value::Number(number) = self else { return None };
+        number.as_str().parse().ok()
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn as_u128(&self) -> Option<u128> {
+        let serde_json::Value::Number(number) = self else { return None };
+        number.as_str().parse().ok()
+    }
+
+    // with `arbitrary_precision` numbers keep the digits they were parsed from
+    fn as_number_str(&self) -> Option<Cow<'_, str>> {
+        let serde_json::Value::Number(number) = self else { return None };
+
+        #[cfg(feature = "arbitrary_precision")]
+        let digits = Cow::Borrowed(number.as_str());
+        #[cfg(not(feature = "arbitrary_precision"))]
+        let digits = Cow::Owned(number.to_string());
+
+        Some(digits)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Navigable for toml::Value {
+    const PATH_STYLE: PathStyle = PathStyle::Toml;
+
+    type Object = toml::Table;
+    type Array = toml::value::Array;
+
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        self.as_table()?.get(key)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.as_array().map(Vec::len)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+        match self {
+            toml::Value::Array(array) => Some(array.iter()
+                .enumerate()
+                .map(|(index, value)| (PathSegment::Index(index), value))
+                .collect()),
+            toml::Value::Table(table) => Some(table.iter()
+                .map(|(key, value)| (PathSegment::Key(key.clone()), value))
+                .collect()),
+            _ => None,
+        }
+    }
+
+    fn as_object(&self) -> Option<&toml::Table> {
+        self.as_table()
+    }
+
+    fn as_array(&self) -> Option<&toml::value::Array> {
+        self.as_array()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.as_integer()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.as_integer().and_then(|integer| u64::try_from(integer).ok())
+    }
+
+    // integers are valid floats, like they are for json
+    fn as_f64(&self) -> Option<f64> {
+        self.as_float().or_else(|| self.as_integer().map(|integer| integer as f64))
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl Navigable for serde_yaml::Value {
+    type Object = serde_yaml::Mapping;
+    type Array = serde_yaml::Sequence;
+
+    // keys that are not strings are matched by how they are written, e.g. `"1"` or `"true"`
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        let mapping = self.as_mapping()?;
+
+        mapping.get(key).or_else(|| {
+            mapping.iter()
+                .find(|(candidate, _)| yaml_key(candidate).as_deref() == Some(key))
+                .map(|(_, value)| value)
+        })
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_sequence()?.get(index)
+    }
+
+    fn get_integer_key(&self, key: i64) -> Option<&Self> {
+        self.as_mapping()?.get(serde_yaml::Value::Number(key.into()))
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.as_sequence().map(Vec::len)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+        if let Some(sequence) = self.as_sequence() {
+            return Some(sequence.iter()
+                .enumerate()
+                .map(|(index, value)| (PathSegment::Index(index), value))
+                .collect());
+        }
+
+        Some(self.as_mapping()?
+            .iter()
+            .map(|(key, value)| (PathSegment::Key(yaml_key(key).unwrap_or_else(|| format!("{key:?}"))), value))
+            .collect())
+    }
+
+    fn as_object(&self) -> Option<&serde_yaml::Mapping> {
+        self.as_mapping()
+    }
+
+    fn as_array(&self) -> Option<&serde_yaml::Sequence> {
+        self.as_sequence()
+    }
+
+    fn is_null(&self) -> bool {
+        self.is_null()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.as_i64()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.as_u64()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.as_f64()
+    }
+}
+
+/// How a scalar mapping key is written, tags are ignored like they are by `serde_yaml`
+#[cfg(feature = "serde_yaml")]
+fn yaml_key(key: &serde_yaml::Value) -> Option<String> {
+    match key {
+        serde_yaml::Value::Null => Some("null".to_owned()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Tagged(tagged) => yaml_key(&tagged.value),
+        serde_yaml::Value::Sequence(_) | serde_yaml::Value::Mapping(_) => None,
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl Navigable for ciborium::Value {
+    type Object = Vec<(ciborium::Value, ciborium::Value)>;
+    type Array = Vec<ciborium::Value>;
+
+    // tags are looked through, like they are for yaml, `; as tagged` keeps them
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        let map = untag_cbor(self).as_map()?;
+
+        map.iter()
+            .find(|(candidate, _)| untag_cbor(candidate).as_text() == Some(key))
+            .or_else(|| map.iter().find(|(candidate, _)| cbor_key(candidate).as_deref() == Some(key)))
+            .map(|(_, value)| value)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        untag_cbor(self).as_array()?.get(index)
+    }
+
+    fn get_integer_key(&self, key: i64) -> Option<&Self> {
+        untag_cbor(self).as_map()?
+            .iter()
+            .find(|(candidate, _)| untag_cbor(candidate).as_integer() == Some(key.into()))
+            .map(|(_, value)| value)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        untag_cbor(self).as_array().map(Vec::len)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+        match untag_cbor(self) {
+            ciborium::Value::Array(array) => Some(array.iter()
+                .enumerate()
+                .map(|(index, value)| (PathSegment::Index(index), value))
+                .collect()),
+            ciborium::Value::Map(map) => Some(map.iter()
+                .map(|(key, value)| (PathSegment::Key(cbor_key(key).unwrap_or_else(|| format!("{key:?}"))), value))
+                .collect()),
+            _ => None,
+        }
+    }
+
+    fn as_object(&self) -> Option<&Vec<(ciborium::Value, ciborium::Value)>> {
+        untag_cbor(self).as_map()
+    }
+
+    fn as_array(&self) -> Option<&Vec<ciborium::Value>> {
+        untag_cbor(self).as_array()
+    }
+
+    fn is_null(&self) -> bool {
+        untag_cbor(self).is_null()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        untag_cbor(self).as_text()
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        untag_cbor(self).as_bool()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        untag_cbor(self).as_integer().and_then(|integer| i64::try_from(integer).ok())
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        untag_cbor(self).as_integer().and_then(|integer| u64::try_from(integer).ok())
+    }
+
+    // integers are valid floats, like they are for json
+    fn as_f64(&self) -> Option<f64> {
+        let value = untag_cbor(self);
+        value.as_float().or_else(|| value.as_integer().map(|integer| i128::from(integer) as f64))
+    }
+
+    // cbor integers go beyond `i64` and `u64` in both directions
+    fn as_i128(&self) -> Option<i128> {
+        untag_cbor(self).as_integer().map(i128::from)
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        untag_cbor(self).as_integer().and_then(|integer| u128::try_from(integer).ok())
+    }
+
+    fn as_number_str(&self) -> Option<Cow<'_, str>> {
+        match untag_cbor(self) {
+            ciborium::Value::Integer(integer) => Some(Cow::Owned(i128::from(*integer).to_string())),
+            ciborium::Value::Float(float) if float.is_finite() => Some(Cow::Owned(format!("{float:?}"))),
+            _ => None,
+        }
+    }
+}
+
+/// The value inside any number of cbor tags
+#[cfg(feature = "ciborium")]
+pub(crate) fn untag_cbor(mut value: &ciborium::Value) -> &ciborium::Value {
+    while let ciborium::Value::Tag(_, tagged) = value {
+        value = tagged;
+    }
+
+    value
+}
+
+/// How a scalar map key is written
+#[cfg(feature = "ciborium")]
+fn cbor_key(key: &ciborium::Value) -> Option<String> {
+    match untag_cbor(key) {
+        ciborium::Value::Null => Some("null".to_owned()),
+        ciborium::Value::Bool(b) => Some(b.to_string()),
+        ciborium::Value::Integer(n) => Some(i128::from(*n).to_string()),
+        ciborium::Value::Float(n) => Some(n.to_string()),
+        ciborium::Value::Text(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl Navigable for rmpv::Value {
+    type Object = Vec<(rmpv::Value, rmpv::Value)>;
+    type Array = Vec<rmpv::Value>;
+
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        let map = self.as_map()?;
+
+        map.iter()
+            .find(|(candidate, _)| candidate.as_str() == Some(key))
+            .or_else(|| map.iter().find(|(candidate, _)| msgpack_key(candidate).as_deref() == Some(key)))
+            .map(|(_, value)| value)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn get_integer_key(&self, key: i64) -> Option<&Self> {
+        self.as_map()?
+            .iter()
+            .find(|(candidate, _)| candidate.as_i64() == Some(key))
+            .map(|(_, value)| value)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.as_array().map(Vec::len)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Self)>> {
+        match self {
+            rmpv::Value::Array(array) => Some(array.iter()
+                .enumerate()
+                .map(|(index, value)| (PathSegment::Index(index), value))
+                .collect()),
+            rmpv::Value::Map(map) => Some(map.iter()
+                .map(|(key, value)| (PathSegment::Key(msgpack_key(key).unwrap_or_else(|| format!("{key:?}"))), value))
+                .collect()),
+            _ => None,
+        }
+    }
+
+    fn as_object(&self) -> Option<&Vec<(rmpv::Value, rmpv::Value)>> {
+        self.as_map()
+    }
+
+    fn as_array(&self) -> Option<&Vec<rmpv::Value>> {
+        self.as_array()
+    }
+
+    fn is_null(&self) -> bool {
+        self.is_nil()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.as_i64()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.as_u64()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.as_f64()
+    }
+}
+
+/// How a scalar map key is written
+#[cfg(feature = "rmpv")]
+fn msgpack_key(key: &rmpv::Value) -> Option<String> {
+    match key {
+        rmpv::Value::Nil => Some("null".to_owned()),
+        rmpv::Value::Boolean(b) => Some(b.to_string()),
+        rmpv::Value::Integer(n) => Some(n.to_string()),
+        rmpv::Value::F32(n) => Some(n.to_string()),
+        rmpv::Value::F64(n) => Some(n.to_string()),
+        rmpv::Value::String(s) => s.as_str().map(str::to_owned),
+        _ => None,
+    }
+}
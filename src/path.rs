@@ -0,0 +1,322 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+
+use crate::Navigable;
+
+/// A single resolved step of a [`NavPath`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, ".{key}"),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// How a [`NavPath`] is rendered by its `Display` implementation
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum PathStyle {
+    /// `root.key[index].key`
+    #[default]
+    Dotted,
+
+    /// An RFC 6901 JSON Pointer, `/key/index/key`
+    Pointer,
+
+    /// An RFC 9535 normalized path, `$['key'][index]['key']`
+    Normalized,
+
+    /// A TOML dotted key, `key[index].key` or `"quoted.key".key`
+    Toml,
+}
+
+/// The concrete location of a value inside a document,
+/// rendered according to its [`PathStyle`]
+#[derive(Clone, Debug)]
+pub struct NavPath {
+    root: Cow<'static, str>,
+    segments: Vec<PathSegment>,
+    style: PathStyle,
+}
+
+impl NavPath {
+    pub fn new(root: impl Into<Cow<'static, str>>) -> Self {
+        NavPath { root: root.into(), segments: Vec::new(), style: PathStyle::default() }
+    }
+
+    pub fn style(&self) -> PathStyle {
+        self.style
+    }
+
+    pub fn with_style(mut self, style: PathStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Returns a copy of this path extended by `segment`
+    pub fn join(&self, segment: PathSegment) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// Renders this path as an RFC 6901 JSON Pointer, e.g. `/payload/features/0`
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+
+        for segment in &self.segments {
+            pointer.push('/');
+
+            match segment {
+                PathSegment::Key(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+                PathSegment::Index(index) => pointer.push_str(&index.to_string()),
+            }
+        }
+
+        pointer
+    }
+
+    /// Renders this path as an RFC 9535 normalized path, e.g. `$['payload']['features'][0]`
+    pub fn to_normalized(&self) -> String {
+        let mut normalized = String::from("$");
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) => {
+                    normalized.push_str("['");
+                    escape_normalized(key, &mut normalized);
+                    normalized.push_str("']");
+                },
+                PathSegment::Index(index) => {
+                    normalized.push_str(&format!("[{index}]"));
+                },
+            }
+        }
+
+        normalized
+    }
+
+    /// Renders this path as a TOML dotted key, quoting keys that are not bare, e.g. `server."v1.2".ports[0]`.
+    /// The root itself is rendered by its name
+    pub fn to_toml(&self) -> String {
+        if self.segments.is_empty() {
+            return self.root.to_string();
+        }
+
+        let mut dotted = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) => {
+                    if !dotted.is_empty() {
+                        dotted.push('.');
+                    }
+
+                    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        dotted.push_str(key);
+                    } else {
+                        dotted.push('"');
+                        escape_toml(key, &mut dotted);
+                        dotted.push('"');
+                    }
+                },
+                PathSegment::Index(index) => {
+                    dotted.push_str(&format!("[{index}]"));
+                },
+            }
+        }
+
+        dotted
+    }
+}
+
+fn escape_normalized(key: &str, out: &mut String) {
+    for c in key.chars() {
+        match c {
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+}
+
+fn escape_toml(key: &str, out: &mut String) {
+    for c in key.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' || c == '\u{7f}' => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+}
+
+impl fmt::Display for NavPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style {
+            PathStyle::Dotted => {
+                f.write_str(&self.root)?;
+                self.segments.iter().try_for_each(|segment| segment.fmt(f))
+            },
+            PathStyle::Pointer => f.write_str(&self.to_pointer()),
+            PathStyle::Normalized => f.write_str(&self.to_normalized()),
+            PathStyle::Toml => f.write_str(&self.to_toml()),
+        }
+    }
+}
+
+/// Paths are equal if they lead to the same location, regardless of how they are rendered
+impl PartialEq for NavPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.segments == other.segments
+    }
+}
+
+impl Eq for NavPath {}
+
+impl Hash for NavPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.root.hash(state);
+        self.segments.hash(state);
+    }
+}
+
+impl PartialEq<str> for NavPath {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl PartialEq<&str> for NavPath {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+/// A path segment as it was written, before it is resolved against a value
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SegmentRef<'s> {
+    Key(&'s str),
+
+    /// An array index, negative indices count from the end of the array.
+    /// Wide enough to keep any index as it was written, even one no array can reach
+    Index(i128),
+}
+
+impl<'s> SegmentRef<'s> {
+    /// Looks up the value this segment refers to together with its concrete path segment
+    pub fn lookup<V: Navigable>(self, value: &V) -> Option<(PathSegment, &V)> {
+        match self {
+            SegmentRef::Key(key) => {
+                let value = value.get_key(key)?;
+                Some((PathSegment::Key(key.to_owned()), value))
+            },
+            SegmentRef::Index(index) => match value.array_len() {
+                Some(len) => {
+                    let index = resolve_index(index, len)?;
+                    Some((PathSegment::Index(index), value.get_index(index)?))
+                },
+                None => {
+                    let value = value.get_integer_key(i64::try_from(index).ok()?)?;
+                    Some((PathSegment::Key(index.to_string()), value))
+                },
+            },
+        }
+    }
+
+    /// Looks up the value this segment refers to in `value` for mutation
+    #[cfg(feature = "serde_json")]
+    pub fn lookup_mut(self, value: &mut Value) -> Option<(PathSegment, &mut Value)> {
+        match self {
+            SegmentRef::Key(key) => {
+                let value = value.as_object_mut()?.get_mut(key)?;
+                Some((PathSegment::Key(key.to_owned()), value))
+            },
+            SegmentRef::Index(index) => {
+                let array = value.as_array_mut()?;
+                let index = resolve_index(index, array.len())?;
+                Some((PathSegment::Index(index), &mut array[index]))
+            },
+        }
+    }
+}
+
+/// Resolves a possibly negative `index` into an array of length `len`
+pub(crate) fn resolve_index(index: i128, len: usize) -> Option<usize> {
+    let index = if index < 0 {
+        len.checked_sub(usize::try_from(index.unsigned_abs()).ok()?)?
+    } else {
+        usize::try_from(index).ok()?
+    };
+
+    (index < len).then_some(index)
+}
+
+/// Anything that can be used as a path segment in `json_nav!`,
+/// i.e. object keys (`str`, `String`) and array indices (any primitive integer)
+pub trait Segment {
+    fn segment_ref(&self) -> SegmentRef<'_>;
+}
+
+impl Segment for str {
+    fn segment_ref(&self) -> SegmentRef<'_> {
+        SegmentRef::Key(self)
+    }
+}
+
+impl Segment for String {
+    fn segment_ref(&self) -> SegmentRef<'_> {
+        SegmentRef::Key(self)
+    }
+}
+
+macro_rules! impl_index_segment {
+    ($($int:ty),+) => {
+        $(
+            impl Segment for $int {
+                fn segment_ref(&self) -> SegmentRef<'_> {
+                    SegmentRef::Index(*self as i128)
+                }
+            }
+        )+
+    };
+}
+
+impl_index_segment!(usize, u8, u16, u32, u64, isize, i8, i16, i32, i64);
+
+impl<T: Segment + ?Sized> Segment for &T {
+    fn segment_ref(&self) -> SegmentRef<'_> {
+        (**self).segment_ref()
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::internal::Cursor;
+use crate::{JsonNavError, NavPath, PathStyle};
+
+/// An RFC 6901 JSON Pointer, errors produced by resolving it render their paths as pointers
+///
+/// ```rust
+/// use serde_json::json;
+/// use json_nav::JsonPointer;
+///
+/// let value = json!({ "a/b": { "m~n": [1, 2] } });
+///
+/// let pointer = JsonPointer::parse("/a~1b/m~0n/1").unwrap();
+/// assert_eq!(Ok(&json!(2)), pointer.resolve(&value));
+///
+/// let error = JsonPointer::parse("/a~1b/m~0n/2").unwrap().resolve(&value);
+/// assert_eq!("index 2 is out of bounds for /a~1b/m~0n of length 2", error.unwrap_err().to_string());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct JsonPointer {
+    source: String,
+    tokens: Vec<String>,
+}
+
+impl JsonPointer {
+    pub fn parse(pointer: &str) -> Result<Self, JsonNavError> {
+        let syntax_error = |position, message| JsonNavError::Syntax { input: pointer.to_owned(), position, message };
+
+        if pointer.is_empty() {
+            return Ok(JsonPointer { source: String::new(), tokens: Vec::new() });
+        }
+
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            return Err(syntax_error(0, "a JSON pointer must be empty or start with `/`"));
+        };
+
+        let mut position = 1;
+        let mut unescaped = Vec::new();
+
+        for token in tokens.split('/') {
+            let mut chars = token.char_indices();
+            let mut unescaped_token = String::with_capacity(token.len());
+
+            while let Some((offset, c)) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some((_, '0')) => unescaped_token.push('~'),
+                        Some((_, '1')) => unescaped_token.push('/'),
+                        _ => return Err(syntax_error(position + offset, "`~` must be followed by `0` or `1`")),
+                    },
+                    c => unescaped_token.push(c),
+                }
+            }
+
+            position += token.len() + 1;
+            unescaped.push(unescaped_token);
+        }
+
+        Ok(JsonPointer { source: pointer.to_owned(), tokens: unescaped })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The unescaped reference tokens of this pointer
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    pub fn resolve<'a>(&self, value: &'a Value) -> Result<&'a Value, JsonNavError> {
+        let mut cursor = Cursor::new(value, NavPath::new("").with_style(PathStyle::Pointer));
+
+        for token in &self.tokens {
+            cursor = match (cursor.value()?, array_index(token)) {
+                (Value::Array(_), Some(index)) => cursor.get(&index)?,
+                _ => cursor.get(token)?,
+            };
+        }
+
+        cursor.into_value()
+    }
+}
+
+/// Parses an array index token, which must not have leading zeros
+fn array_index(token: &str) -> Option<usize> {
+    let is_index = token == "0" || (!token.starts_with('0') && token.chars().all(|c| c.is_ascii_digit()));
+
+    is_index.then(|| token.parse().ok()).flatten()
+}
+
+impl FromStr for JsonPointer {
+    type Err = JsonNavError;
+
+    fn from_str(pointer: &str) -> Result<Self, JsonNavError> {
+        JsonPointer::parse(pointer)
+    }
+}
+
+impl fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
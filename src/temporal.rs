@@ -0,0 +1,271 @@
+//! The `; as datetime`, `; as date`, `; as time` and `; as duration` conversions, backed by `chrono`
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+use crate::internal::Cursor;
+use crate::{JsonNavError, Navigable};
+
+/// How a date, time or duration is written, `; as <target> format <format>` selects one,
+/// a string converts to [`TimeFormat::Custom`]
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use chrono::{NaiveDate, TimeDelta};
+/// use serde_json::json;
+/// use json_nav::{json_nav, TimeFormat};
+///
+/// let job = json!({ "created": 1700000000123i64, "due": "24/12/2024", "timeout": 90, "retry": "PT1M30.5S" });
+///
+/// let created = json_nav! { job => "created"; as datetime format TimeFormat::Millis }.unwrap();
+/// assert_eq!(123, created.timestamp_subsec_millis());
+///
+/// assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 24), json_nav! { job => "due"; as date format "%d/%m/%Y" }.ok());
+/// assert_eq!(Ok(TimeDelta::seconds(90)), json_nav! { job => "timeout"; as duration });
+/// assert_eq!(Ok(TimeDelta::milliseconds(90_500)), json_nav! { job => "retry"; as duration });
+///
+/// let error = json_nav! { job => "due"; as date };
+/// assert_eq!("could not parse 24/12/2024 at job.due, expected an ISO 8601 date", error.unwrap_err().to_string());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TimeFormat {
+    /// RFC 3339 datetimes, ISO 8601 dates, times and durations, numbers are Unix seconds
+    /// for datetimes and seconds for durations
+    #[default]
+    Iso8601,
+    /// Unix seconds for datetimes and dates, seconds for durations
+    Seconds,
+    /// Unix milliseconds for datetimes and dates, milliseconds for durations
+    Millis,
+    /// A `chrono` format string like `"%d/%m/%Y"`, for datetimes, dates and times
+    Custom(&'static str),
+}
+
+impl From<&'static str> for TimeFormat {
+    fn from(format: &'static str) -> Self {
+        TimeFormat::Custom(format)
+    }
+}
+
+/// A date, time or duration `json_nav!` converts to
+pub trait Temporal: Sized {
+    /// The name of the target, for values that are neither strings nor numbers
+    const TARGET: &'static str;
+
+    /// What a value in `format` looks like, for errors
+    fn expected(format: TimeFormat) -> String;
+
+    fn parse(s: &str, format: TimeFormat) -> Option<Self>;
+
+    fn from_integer(n: i64, format: TimeFormat) -> Option<Self>;
+}
+
+/// Converts the value under `x`, reporting anything that doesn't follow `format` with its path
+pub fn parse_time<T: Temporal, V: Navigable>(x: Cursor<'_, V>, format: TimeFormat) -> Result<T, JsonNavError> {
+    let value = x.value()?;
+
+    let (raw, parsed) = match (value.as_str(), value.as_i64()) {
+        // unix timestamps are often sent as strings
+        (Some(s), _) if matches!(format, TimeFormat::Seconds | TimeFormat::Millis) => {
+            (s.to_owned(), s.trim().parse().ok().and_then(|n| T::from_integer(n, format)))
+        },
+        (Some(s), _) => (s.to_owned(), T::parse(s, format)),
+        (None, Some(n)) => (n.to_string(), T::from_integer(n, format)),
+        (None, None) => match (value.as_u64(), value.as_f64()) {
+            (Some(n), _) => (n.to_string(), None),
+            (None, Some(n)) => (format!("{n:?}"), None),
+            (None, None) => return Err(JsonNavError::TypeMismatch { expected: T::TARGET }),
+        },
+    };
+
+    parsed.ok_or_else(|| JsonNavError::InvalidTime { path: x.path().clone(), value: raw, expected: T::expected(format) })
+}
+
+/// How `format` describes a number or a custom string, `None` for ISO 8601
+fn expected(format: TimeFormat, unix: &str) -> Option<String> {
+    match format {
+        TimeFormat::Iso8601 => None,
+        TimeFormat::Seconds => Some(format!("{unix}seconds")),
+        TimeFormat::Millis => Some(format!("{unix}milliseconds")),
+        TimeFormat::Custom(format) => Some(format!("the format {format:?}")),
+    }
+}
+
+impl Temporal for DateTime<FixedOffset> {
+    const TARGET: &'static str = "datetime";
+
+    fn expected(format: TimeFormat) -> String {
+        expected(format, "Unix ").unwrap_or_else(|| "an RFC 3339 datetime or Unix seconds".to_owned())
+    }
+
+    // custom formats without an offset are read as UTC
+    fn parse(s: &str, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Iso8601 => DateTime::parse_from_rfc3339(s).ok(),
+            TimeFormat::Custom(format) => DateTime::parse_from_str(s, format).ok()
+                .or_else(|| Some(NaiveDateTime::parse_from_str(s, format).ok()?.and_utc().fixed_offset())),
+            TimeFormat::Seconds | TimeFormat::Millis => None,
+        }
+    }
+
+    fn from_integer(n: i64, format: TimeFormat) -> Option<Self> {
+        let datetime = match format {
+            TimeFormat::Iso8601 | TimeFormat::Seconds => DateTime::from_timestamp(n, 0),
+            TimeFormat::Millis => DateTime::from_timestamp_millis(n),
+            TimeFormat::Custom(_) => None,
+        };
+
+        datetime.map(|datetime| datetime.fixed_offset())
+    }
+}
+
+impl Temporal for NaiveDate {
+    const TARGET: &'static str = "date";
+
+    fn expected(format: TimeFormat) -> String {
+        expected(format, "Unix ").unwrap_or_else(|| "an ISO 8601 date".to_owned())
+    }
+
+    fn parse(s: &str, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Iso8601 => s.parse().ok(),
+            TimeFormat::Custom(format) => NaiveDate::parse_from_str(s, format).ok(),
+            TimeFormat::Seconds | TimeFormat::Millis => None,
+        }
+    }
+
+    // the date of a timestamp in UTC
+    fn from_integer(n: i64, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Seconds | TimeFormat::Millis => DateTime::<FixedOffset>::from_integer(n, format).map(|datetime| datetime.date_naive()),
+            TimeFormat::Iso8601 | TimeFormat::Custom(_) => None,
+        }
+    }
+}
+
+impl Temporal for NaiveTime {
+    const TARGET: &'static str = "time";
+
+    fn expected(format: TimeFormat) -> String {
+        match format {
+            TimeFormat::Custom(format) => format!("the format {format:?}"),
+            _ => "an ISO 8601 time".to_owned(),
+        }
+    }
+
+    fn parse(s: &str, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Iso8601 => s.parse().ok(),
+            TimeFormat::Custom(format) => NaiveTime::parse_from_str(s, format).ok(),
+            TimeFormat::Seconds | TimeFormat::Millis => None,
+        }
+    }
+
+    fn from_integer(_n: i64, _format: TimeFormat) -> Option<Self> {
+        None
+    }
+}
+
+impl Temporal for TimeDelta {
+    const TARGET: &'static str = "duration";
+
+    fn expected(format: TimeFormat) -> String {
+        expected(format, "").unwrap_or_else(|| "an ISO 8601 duration or seconds".to_owned())
+    }
+
+    fn parse(s: &str, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Iso8601 => parse_duration(s),
+            _ => None,
+        }
+    }
+
+    fn from_integer(n: i64, format: TimeFormat) -> Option<Self> {
+        match format {
+            TimeFormat::Iso8601 | TimeFormat::Seconds => TimeDelta::try_seconds(n),
+            TimeFormat::Millis => TimeDelta::try_milliseconds(n),
+            TimeFormat::Custom(_) => None,
+        }
+    }
+}
+
+/// Parses an ISO 8601 duration like `P1DT2H30M` or `-PT0.5S`, years and months are rejected
+/// because they don't have a fixed length
+fn parse_duration(s: &str) -> Option<TimeDelta> {
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (-1, s),
+        None => (1, s),
+    };
+
+    let s = s.strip_prefix('P')?;
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+
+    let date = components(date, &[('W', 7 * 86_400), ('D', 86_400)])?;
+    let time = match time {
+        Some(time) => Some(components(time, &[('H', 3_600), ('M', 60), ('S', 1)])?),
+        None => None,
+    };
+
+    // a `T` has to be followed by a component, and there has to be at least one
+    if time.as_ref().is_some_and(|time| !time.any) || !date.any && time.is_none() {
+        return None;
+    }
+
+    // only the last component can have a fraction
+    if date.fraction && time.is_some() {
+        return None;
+    }
+
+    let time = time.map_or(0, |time| time.nanos);
+
+    let nanos = sign * date.nanos.checked_add(time)?;
+    let seconds = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+    TimeDelta::new(seconds, nanos.rem_euclid(1_000_000_000) as u32)
+}
+
+/// One half of a duration, before or after the `T`
+struct Components {
+    nanos: i128,
+    /// Whether there was any component
+    any: bool,
+    /// Whether the last component had a fraction
+    fraction: bool,
+}
+
+/// Sums the components of one half of a duration in nanoseconds, each unit appears at most once and in order.
+/// Only the last component can have a fraction, separated by `.` or `,`
+fn components(mut s: &str, units: &[(char, i128)]) -> Option<Components> {
+    let mut components = Components { nanos: 0, any: false, fraction: false };
+
+    for &(unit, seconds) in units {
+        let Some(end) = s.find(unit) else { continue };
+
+        if components.fraction {
+            return None;
+        }
+
+        let (whole, fraction) = match s[..end].split_once(['.', ',']) {
+            Some((_, "")) => return None,
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (&s[..end], None),
+        };
+        let digits = fraction.unwrap_or_default();
+        if whole.is_empty() || !whole.bytes().chain(digits.bytes()).all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        // digits beyond nanoseconds are dropped
+        let nanos: i128 = format!("{digits:0<9}")[..9].parse().ok()?;
+        let component = whole.parse::<i128>().ok()?.checked_mul(1_000_000_000)?.checked_add(nanos)?.checked_mul(seconds)?;
+
+        components.nanos = component.checked_add(components.nanos)?;
+        components.any = true;
+        components.fraction = fraction.is_some();
+        s = &s[end + 1..];
+    }
+
+    s.is_empty().then_some(components)
+}
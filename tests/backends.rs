@@ -0,0 +1,111 @@
+//! Conversions and filters behave the same on every value type
+
+#![cfg(any(feature = "serde_json", feature = "toml", feature = "serde_yaml", feature = "ciborium", feature = "rmpv"))]
+
+use json_nav::{json_nav, JsonNavError};
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_floats_are_not_integers() {
+    let value = serde_json::json!({ "a": 1000.0 });
+
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "a"; as u64 });
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_floats_are_not_integers() {
+    let value: toml::Value = toml::from_str("a = 1000.0").unwrap();
+
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "a"; as u64 });
+    assert_eq!(Ok("1000.0"), json_nav! { value => "a"; as number_str }.as_deref());
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_floats_are_not_integers() {
+    let value: serde_yaml::Value = serde_yaml::from_str("{ a: 1000.0, b: 1.0e30, c: 1.0 }").unwrap();
+
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "a"; as u64 });
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "b"; as u64 });
+    assert_eq!(Ok("1.0"), json_nav! { value => "c"; as str lenient }.as_deref());
+}
+
+#[cfg(feature = "ciborium")]
+#[test]
+fn cbor_floats_are_not_integers() {
+    let value = ciborium::Value::Map(vec![
+        ("a".into(), ciborium::Value::Float(1000.0)),
+        ("b".into(), ciborium::Value::Integer(u64::MAX.into())),
+    ]);
+
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "a"; as u64 });
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "i32" }), json_nav! { value => "a"; as i32 });
+    assert_eq!(Ok(u64::MAX as u128), json_nav! { value => "b"; as u128 });
+}
+
+#[cfg(feature = "rmpv")]
+#[test]
+fn msgpack_floats_are_not_integers() {
+    let value = rmpv::Value::Map(vec![("a".into(), rmpv::Value::F64(1000.0))]);
+
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "u64" }), json_nav! { value => "a"; as u64 });
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_filters_compare_scalars() {
+    let value: serde_yaml::Value = serde_yaml::from_str("
+        items:
+          - { type: x, n: 1, weight: 2.5 }
+          - { type: y, n: 2, weight: 0.5 }
+          - { type: x, n: 3, weight: 1 }
+    ").unwrap();
+
+    assert_eq!(Ok(vec![1, 3]), json_nav! { value => "items" => [?("type" == "x")] => "n"; as u64 });
+    assert_eq!(Ok(vec![2]), json_nav! { value => "items" => [?("weight" < 1)] => "n"; as u64 });
+    assert_eq!(Ok(vec![2, 3]), json_nav! { value => "items" => [?("n" >= 2)] => "n"; as u64 });
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_filters_compare_scalars() {
+    let value: toml::Value = toml::from_str("
+        [[items]]
+        name = 'a'
+        enabled = true
+        [[items]]
+        name = 'b'
+        enabled = false
+    ").unwrap();
+
+    assert_eq!(Ok(vec!["a"]), json_nav! { value => "items" => [?("enabled" == true)] => "name"; as str });
+    assert_eq!(Ok(vec!["b"]), json_nav! { value => "items" => [?("name" >= "b")] => "name"; as str });
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_collections_convert_through_from_json_nav() {
+    use json_nav::FromJsonNav;
+    use serde_json::{Map, Value};
+
+    fn first<'a, T: FromJsonNav<'a, Value>>(value: &'a Value) -> Result<T, JsonNavError> {
+        json_nav! { value => "items" => [first]; as T }
+    }
+
+    let value = serde_json::json!({ "items": [{ "id": 1 }, [2]] });
+
+    assert_eq!(Ok(1), json_nav! { value => "items" => 0; as &Map<String, Value> }.map(Map::len));
+    assert_eq!(Ok(1), json_nav! { value => "items" => 1; as &Vec<Value> }.map(Vec::len));
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "array" }), first::<&Vec<Value>>(&value).map(Vec::len));
+    assert!(first::<&Map<String, Value>>(&value).is_ok());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_collections_convert_through_from_json_nav() {
+    let value: toml::Value = toml::from_str("[server]\nports = [80, 443]").unwrap();
+
+    assert_eq!(Ok(1), json_nav! { value => "server"; as &toml::Table }.map(toml::Table::len));
+    assert_eq!(Ok(2), json_nav! { value => "server" => "ports"; as &toml::value::Array }.map(Vec::len));
+}
@@ -0,0 +1,59 @@
+//! Numeric conversions at the edges of the target ranges
+
+#![cfg(feature = "serde_json")]
+
+use json_nav::{json_nav, FloatConversion, JsonNavError, Overflow, Policy};
+use serde_json::json;
+
+const INTEGRAL: Policy = Policy::STRICT.floats(FloatConversion::Integral);
+
+#[test]
+fn floats_above_i128_fit_u128() {
+    let value = json!({ "large": 2e38, "power": 170141183460469231731687303715884105728.0, "huge": 4e38 });
+
+    assert_eq!(Ok(2e38 as u128), json_nav! { value => "large"; as u128 with INTEGRAL });
+    assert!(2e38 as u128 > i128::MAX as u128);
+    assert_eq!(Ok(1 << 127), json_nav! { value => "power"; as u128 with INTEGRAL });
+
+    let error = json_nav! { value => "large"; as i128 with INTEGRAL };
+    assert!(matches!(error, Err(JsonNavError::OutOfRange { target: "i128", .. })), "{error:?}");
+
+    let error = json_nav! { value => "huge"; as u128 with INTEGRAL };
+    assert!(matches!(error, Err(JsonNavError::OutOfRange { target: "u128", .. })), "{error:?}");
+}
+
+#[test]
+fn floats_saturate_at_the_u128_boundary() {
+    let value = json!({ "large": 2e38, "huge": 4e38, "negative": -4e38 });
+    let saturate = INTEGRAL.overflow(Overflow::Saturate);
+
+    assert_eq!(Ok(2e38 as u128), json_nav! { value => "large"; as u128 with saturate });
+    assert_eq!(Ok(u128::MAX), json_nav! { value => "huge"; as u128 with saturate });
+    assert_eq!(Ok(i128::MAX), json_nav! { value => "large"; as i128 with saturate });
+    assert_eq!(Ok(0), json_nav! { value => "negative"; as u128 with saturate });
+    assert_eq!(Ok(i128::MIN), json_nav! { value => "negative"; as i128 with saturate });
+}
+
+#[test]
+fn errors_report_numbers_as_written() {
+    let value = json!({ "exponent": "1.2e40", "fraction": " 2.5e-1 " });
+    let lenient = INTEGRAL.lenient(true);
+
+    let error = json_nav! { value => "exponent"; as u8 with lenient };
+    assert!(matches!(&error, Err(JsonNavError::OutOfRange { value, target: "u8", .. }) if value == "1.2e40"), "{error:?}");
+
+    let error = json_nav! { value => "fraction"; as u8 with lenient };
+    assert!(matches!(&error, Err(JsonNavError::InvalidNumber { value, .. }) if value == "2.5e-1"), "{error:?}");
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn errors_keep_the_digits_of_arbitrary_precision_numbers() {
+    let value: serde_json::Value = serde_json::from_str(r#"{ "large": 1.0e+30, "fraction": 12.50e-1 }"#).unwrap();
+
+    let error = json_nav! { value => "large"; as u64 with INTEGRAL };
+    assert!(matches!(&error, Err(JsonNavError::OutOfRange { value, .. }) if value == "1.0e+30"), "{error:?}");
+
+    let error = json_nav! { value => "fraction"; as u64 with INTEGRAL };
+    assert!(matches!(&error, Err(JsonNavError::InvalidNumber { value, .. }) if value == "12.50e-1"), "{error:?}");
+}
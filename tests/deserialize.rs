@@ -0,0 +1,16 @@
+//! Errors from `; deserialize` read like the ones serde_json reports
+
+#![cfg(feature = "serde_json")]
+
+use json_nav::json_nav;
+
+#[test]
+fn trailing_elements_are_reported_like_serde_json() {
+    let value = serde_json::json!({ "pair": [1, 2, 3] });
+
+    let expected = serde_json::from_value::<(u8, u8)>(value["pair"].clone()).unwrap_err().to_string();
+    let error = json_nav! { value => "pair"; deserialize (u8, u8) }.unwrap_err().to_string();
+
+    assert_eq!("invalid length 3, expected fewer elements in array", expected);
+    assert_eq!("could not deserialize value.pair, invalid length 3, expected fewer elements in array at value.pair", error);
+}
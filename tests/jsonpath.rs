@@ -0,0 +1,227 @@
+//! Cases modelled on the JSONPath compliance test suite and the examples of RFC 9535
+
+#![cfg(feature = "serde_json")]
+
+use std::time::{Duration, Instant};
+
+use json_nav::{JsonNavError, JsonPath};
+use serde_json::{json, Value};
+
+fn select(query: &str, document: &Value) -> Vec<Value> {
+    JsonPath::parse(query)
+        .unwrap_or_else(|error| panic!("{query} should parse: {error}"))
+        .query(document)
+        .into_iter()
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+fn syntax_error(query: &str) -> usize {
+    match JsonPath::parse(query) {
+        Err(JsonNavError::Syntax { position, .. }) => position,
+        other => panic!("{query} should be a syntax error, got {other:?}"),
+    }
+}
+
+/// Runs `f` on a thread with a small stack, so unbounded recursion fails the test instead of hiding in a big main stack
+fn bounded<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    let started = Instant::now();
+    let result = std::thread::Builder::new()
+        .stack_size(1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .expect("should not overflow the stack");
+
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    result
+}
+
+#[test]
+fn slice_with_negative_step() {
+    let digits = json!([0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    assert_eq!(vec![json!(5), json!(3)], select("$[5:1:-2]", &digits));
+    assert_eq!(vec![json!(9), json!(8), json!(7)], select("$[:-4:-1]", &digits));
+    assert_eq!(vec![json!(9), json!(7), json!(5), json!(3), json!(1)], select("$[::-2]", &digits));
+    assert_eq!(vec![json!(2), json!(1), json!(0)], select("$[2::-1]", &digits));
+    assert_eq!(Vec::<Value>::new(), select("$[1:5:-1]", &digits));
+}
+
+#[test]
+fn slice_bounds() {
+    let digits = json!([0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    assert_eq!(vec![json!(7), json!(8)], select("$[-3:-1]", &digits));
+    assert_eq!(vec![json!(0), json!(1)], select("$[-100:2]", &digits));
+    assert_eq!(vec![json!(8), json!(9)], select("$[8:100]", &digits));
+    assert_eq!(Vec::<Value>::new(), select("$[0:10:0]", &digits));
+    assert_eq!(Vec::<Value>::new(), select("$.a[1:2]", &json!({ "a": { "1": 1 } })));
+}
+
+#[test]
+fn nothing_compared_with_nothing() {
+    let document = json!([{ "a": 1, "b": 1 }, { "a": 1 }, {}, { "a": null, "b": null }]);
+
+    // two empty node lists are equal, an empty node list is not equal to null
+    assert_eq!(vec![document[0].clone(), document[2].clone(), document[3].clone()], select("$[?@.a==@.b]", &document));
+    assert_eq!(vec![document[1].clone()], select("$[?@.a!=@.b]", &document));
+    assert_eq!(vec![document[1].clone()], select("$[?@.x==@.y && @.a!=@.b]", &document));
+    assert_eq!(Vec::<Value>::new(), select("$[?@.x<@.y]", &document));
+    assert_eq!(document.as_array().unwrap().clone(), select("$[?@.x<=@.y]", &document));
+}
+
+#[test]
+fn comparisons_of_different_types() {
+    let document = json!([1, "1", true, null, [1], { "a": 1 }, 1.0]);
+
+    assert_eq!(vec![json!(1), json!(1.0)], select("$[?@==1]", &document));
+    assert_eq!(vec![json!("1")], select("$[?@=='1']", &document));
+    assert_eq!(vec![json!(null)], select("$[?@==null]", &document));
+    assert_eq!(Vec::<Value>::new(), select("$[?@<true]", &document));
+    assert_eq!(vec![json!([1])], select("$[?@==$[4]]", &document));
+}
+
+#[test]
+fn function_type_checking() {
+    // length() takes a value, not a node list that may hold several nodes
+    syntax_error("$[?length(@.*)<3]");
+    // count() takes a node list
+    syntax_error("$[?count(1)>0]");
+    // match() takes two arguments
+    syntax_error("$[?match(@.a)]");
+    // a value result can't be used as a test
+    syntax_error("$[?length(@.a)]");
+    // a logical result can't be compared
+    syntax_error("$[?match(@.a, 'a')==true]");
+    syntax_error("$[?unknown(@.a)]");
+
+    let document = json!([{ "a": "ab" }, { "a": [1, 2, 3] }, { "a": { "b": 1 } }]);
+    assert_eq!(vec![document[0].clone(), document[2].clone()], select("$[?length(@.a)<3]", &document));
+    assert_eq!(vec![document[1].clone()], select("$[?count(@.a.*)==3]", &document));
+    assert_eq!(vec![document[2].clone()], select("$[?value(@.a.b)==1]", &document));
+}
+
+#[test]
+fn string_escapes() {
+    let document = json!({ "a'b": 1, "a\"b": 2, "\u{1D11E}": 3, "tab\there": 4, "é": 5 });
+
+    assert_eq!(vec![json!(1)], select(r"$['a\'b']", &document));
+    assert_eq!(vec![json!(2)], select(r#"$["a\"b"]"#, &document));
+    assert_eq!(vec![json!(3)], select(r"$['𝄞']", &document));
+    assert_eq!(vec![json!(4)], select(r"$['tab\there']", &document));
+    assert_eq!(vec![json!(5)], select(r"$['é']", &document));
+    assert_eq!(vec![json!(5)], select("$.é", &document));
+}
+
+#[test]
+fn invalid_string_escapes() {
+    // unpaired surrogates
+    syntax_error(r"$['\uD834']");
+    syntax_error(r"$['\uDD1E']");
+    syntax_error(r"$['\uD834A']");
+
+    syntax_error(r"$['\a']");
+    syntax_error(r#"$['\"']"#);
+    syntax_error(r"$['\u12']");
+    syntax_error("$['\u{1}']");
+}
+
+#[test]
+fn regexp_classes_and_quantifiers() {
+    let document = json!(["a", "ab", "abb", "abbb", "b", "a\nb", "ä", "é9", "-"]);
+
+    assert_eq!(vec![json!("abb"), json!("abbb")], select("$[?match(@, 'ab{2,3}')]", &document));
+    assert_eq!(vec![json!("ab"), json!("abb")], select("$[?match(@, 'ab{1,2}')]", &document));
+    assert_eq!(vec![json!("abbb")], select("$[?match(@, 'ab{3,}')]", &document));
+    assert_eq!(vec![json!("a"), json!("ab")], select("$[?match(@, 'ab?')]", &document));
+    assert_eq!(vec![json!("b"), json!("ä"), json!("-")], select("$[?match(@, '[^a]')]", &document));
+    assert_eq!(vec![json!("ä"), json!("é9")], select("$[?match(@, '[à-ü][0-9]*')]", &document));
+    assert_eq!(vec![json!("-")], select("$[?match(@, '[-]')]", &document));
+    assert_eq!(vec![json!("a"), json!("ab"), json!("abb")], select("$[?match(@, '(a|ab|abb)')]", &document));
+}
+
+#[test]
+fn regexp_category_escapes() {
+    let document = json!(["a", "Ä", "7", "٣", " ", "-", "a1", "€"]);
+
+    assert_eq!(vec![json!("a"), json!("Ä")], select(r"$[?match(@, '\\p{L}')]", &document));
+    assert_eq!(vec![json!("Ä")], select(r"$[?match(@, '\\p{Lu}')]", &document));
+    assert_eq!(vec![json!("7"), json!("٣")], select(r"$[?match(@, '\\p{Nd}')]", &document));
+    assert_eq!(vec![json!(" ")], select(r"$[?match(@, '\\p{Zs}')]", &document));
+    assert_eq!(
+        vec![json!("7"), json!("٣"), json!(" "), json!("-"), json!("€")],
+        select(r"$[?match(@, '\\P{L}')]", &document),
+    );
+    assert_eq!(vec![json!("a1")], select(r"$[?match(@, '\\p{Ll}\\p{N}')]", &document));
+    assert_eq!(vec![json!("-"), json!("€")], select(r"$[?match(@, '[\\p{P}\\p{Sc}]')]", &document));
+    assert_eq!(vec![json!("a"), json!("Ä"), json!("a1")], select(r"$[?search(@, '[^\\P{L}]')]", &document));
+}
+
+#[test]
+fn regexp_dot_and_anchoring() {
+    let document = json!(["a", "axb", "a\nb", "a\rb", "xa.b"]);
+
+    // `.` does not match line breaks
+    assert_eq!(vec![json!("axb")], select("$[?match(@, 'a.b')]", &document));
+    assert_eq!(vec![json!("axb"), json!("xa.b")], select("$[?search(@, 'a.b')]", &document));
+    assert_eq!(vec![json!("xa.b")], select(r"$[?search(@, 'a\\.b')]", &document));
+    assert_eq!(vec![json!("a")], select("$[?match(@, 'a')]", &document));
+}
+
+#[test]
+fn invalid_regexp_never_matches() {
+    let document = json!(["a", "(", "\\d"]);
+
+    for pattern in ["(", "a{2,1}", "[b-a]", r"\d", "a**", "[]", "^a", "a$", r"\p{Cs}", r"\p{Lx}", r"\pL", r"\p{L", r"[a-\p{L}]"] {
+        let query = format!("$[?match(@, '{}')]", pattern.replace('\\', "\\\\"));
+        assert_eq!(Vec::<Value>::new(), select(&query, &document), "{pattern}");
+    }
+
+    let patterns = json!({ "items": ["a", "b"], "pattern": "(" });
+    assert_eq!(Vec::<Value>::new(), select("$.items[?match(@, $.pattern)]", &patterns));
+}
+
+#[test]
+fn regexp_runs_in_linear_time() {
+    bounded(|| {
+        let document = json!(["a".repeat(30), "a".repeat(200_000)]);
+
+        assert_eq!(Vec::<Value>::new(), select("$[?match(@, '(a|a)*b')]", &document));
+        assert_eq!(Vec::<Value>::new(), select("$[?match(@, '(a*)*b')]", &document));
+        assert_eq!(2, select("$[?match(@, '.*')]", &document).len());
+        assert_eq!(2, select("$[?search(@, '(a|a)*a')]", &document).len());
+    });
+}
+
+#[test]
+fn deep_nesting_is_a_syntax_error() {
+    bounded(|| {
+        let query = format!("$[?{}@.a{}]", "(".repeat(100_000), ")".repeat(100_000));
+        match JsonPath::parse(&query) {
+            Err(JsonNavError::Syntax { message, .. }) => assert_eq!("expression nested too deeply", message),
+            other => panic!("should be a syntax error, got {other:?}"),
+        }
+
+        let query = format!("$[?{}]", "@[?".repeat(100_000));
+        syntax_error(&query);
+
+        let query = format!("$[?{}@{}]", "!(".repeat(100_000), ")".repeat(100_000));
+        syntax_error(&query);
+    });
+
+    let nested = format!("$[?{}@.a{}]", "(".repeat(20), ")".repeat(20));
+    assert_eq!(vec![json!({ "a": 1 })], select(&nested, &json!([{ "a": 1 }, {}])));
+}
+
+#[test]
+fn error_positions() {
+    assert_eq!(0, syntax_error(""));
+    assert_eq!(0, syntax_error("a"));
+    assert_eq!(4, syntax_error("$.a[01]"));
+    assert_eq!(2, syntax_error("$[-0]"));
+    assert_eq!(1, syntax_error("$ "));
+    assert_eq!(2, syntax_error("$.1"));
+    assert_eq!(5, syntax_error("$['a'"));
+    assert_eq!(2, syntax_error("$[9007199254740992]"));
+}
@@ -0,0 +1,105 @@
+//! The path grammar of `json_nav!`
+
+#![cfg(feature = "serde_json")]
+
+use json_nav::{json_nav, JsonNavError};
+use serde_json::json;
+
+#[test]
+fn long_paths_of_runtime_segments() {
+    let keys = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+    let cfg = json!({ "a": { "b": { "c": { "d": { "e": { "f": { "g": { "h": { "i": { "j": { "k": { "l": [7] } } } } } } } } } } } });
+
+    let value = json_nav! {
+        cfg => keys[0] => keys[1] => keys[2] => keys[3] => keys[4] => keys[5]
+            => keys[6] => keys[7] => keys[8] => keys[9] => keys[10] => keys[11] => [0]; as u64
+    };
+    assert_eq!(Ok(7u64), value);
+
+    let value = json_nav! {
+        cfg => keys[0] => keys[1] => keys[2] => keys[3] => keys[4] => keys[5]
+            => keys[6] => keys[7] => keys[8] => keys[9] => keys[10] => keys[11] => [1]; as u64 or 0
+    };
+    assert_eq!(Ok(0u64), value);
+
+    let value = json_nav! {
+        cfg => keys[0] => keys[1] => keys[2] => keys[3] => keys[4] => keys[5]
+            => keys[6] => keys[7] => keys[8] => keys[9] => keys[10] => keys[11] => keys.len() - 11
+    };
+    assert!(matches!(value, Err(JsonNavError::OutOfBounds { index: 1, len: 1, .. })), "{value:?}");
+}
+
+#[test]
+fn optional_values_of_a_fan_out() {
+    let value = json!({ "items": [{ "p": 1.5 }, { "q": 2 }, { "p": "x" }] });
+
+    let prices: Vec<_> = json_nav! { value => "items" => * }
+        .unwrap()
+        .into_iter()
+        .map(|item| json_nav! { item => "p"; as f64? })
+        .collect();
+
+    assert_eq!(vec![Ok(Some(1.5)), Ok(None), Err(JsonNavError::TypeMismatch { expected: "f64" })], prices);
+}
+
+#[test]
+fn defaults_for_values_of_a_fan_out() {
+    let value = json!({ "items": [{ "p": 1.5 }, { "q": 2 }, { "p": "x" }] });
+
+    let prices: Result<Vec<_>, _> = json_nav! { value => "items" => * }
+        .unwrap()
+        .into_iter()
+        .map(|item| json_nav! { item => "p"; as f64 or 0.0 })
+        .collect();
+    assert_eq!(Err(JsonNavError::TypeMismatch { expected: "f64" }), prices);
+
+    let prices: Vec<_> = json_nav! { value => "items" => * }
+        .unwrap()
+        .into_iter()
+        .map(|item| json_nav! { item => "p"; as f64 or_any f64::NAN })
+        .collect();
+    assert_eq!(Ok(1.5), prices[0]);
+    assert!(prices[1..].iter().all(|price| price.as_ref().is_ok_and(|price| price.is_nan())));
+}
+
+#[test]
+fn indices_are_reported_as_written() {
+    let arr = json!([1]);
+
+    let error = json_nav! { arr => usize::MAX };
+    assert_eq!("index 18446744073709551615 is out of bounds for arr of length 1", error.unwrap_err().to_string());
+
+    let error = json_nav! { arr => i64::MIN };
+    assert!(matches!(error, Err(JsonNavError::OutOfBounds { index, len: 1, .. }) if index == i64::MIN.into()), "{error:?}");
+
+    assert_eq!(Ok(vec![1u64]), json_nav! { arr => [0..u64::MAX]; as u64 });
+    assert_eq!(Ok(vec![1u64]), json_nav! { arr => [i64::MIN..]; as u64 });
+}
+
+#[test]
+fn filters_compare_to_null() {
+    let value = json!({
+        "users": [
+            { "name": "a", "deleted_at": null },
+            { "name": "b", "deleted_at": "2024-01-01" },
+            { "name": "c" }
+        ]
+    });
+
+    assert_eq!(Ok(vec!["a"]), json_nav! { value => "users" => [?("deleted_at" == null)] => "name"; as str });
+    assert_eq!(Ok(vec!["b"]), json_nav! { value => "users" => [?("deleted_at" != null)] => "name"; as str });
+
+    let error = json_nav! { value => "users" => [?("deleted_at" > null)] };
+    assert!(matches!(error, Err(JsonNavError::Filtered { candidates: 3, .. })), "{error:?}");
+}
+
+#[test]
+fn double_star_searches_like_double_dot() {
+    let value = json!({ "a": { "id": 1, "b": [{ "id": 2 }] } });
+
+    let dots = json_nav! { value => .. "id"; as u64 }.unwrap();
+    let stars = json_nav! { value => ** "id"; as u64 }.unwrap();
+    assert_eq!(dots, stars);
+
+    assert_eq!(Ok(vec![2u64]), json_nav! { value => "a" => "b" => [?(** "id" > 1)] => "id"; as u64 });
+}
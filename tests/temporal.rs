@@ -0,0 +1,39 @@
+//! ISO 8601 durations at the edges of the grammar
+
+#![cfg(all(feature = "chrono", feature = "serde_json"))]
+
+use chrono::TimeDelta;
+use json_nav::{json_nav, JsonNavError};
+use serde_json::json;
+
+#[test]
+fn durations_that_overflow_are_invalid() {
+    let value = json!({ "seconds": "PT99999999999999999999999999999999S", "weeks": "P9999999999999999999W" });
+
+    let error = json_nav! { value => "seconds"; as duration };
+    assert!(matches!(error, Err(JsonNavError::InvalidTime { .. })), "{error:?}");
+
+    let error = json_nav! { value => "weeks"; as duration };
+    assert!(matches!(error, Err(JsonNavError::InvalidTime { .. })), "{error:?}");
+}
+
+#[test]
+fn only_the_last_component_has_a_fraction() {
+    let value = json!({ "last": "PT1H1.5M", "hours": "PT1.5H2M", "days": "P1.5DT2H", "empty": "PT1.S" });
+
+    assert_eq!(Ok(TimeDelta::seconds(3_690)), json_nav! { value => "last"; as duration });
+
+    for key in ["hours", "days", "empty"] {
+        let error = json_nav! { value => key; as duration };
+        assert!(matches!(error, Err(JsonNavError::InvalidTime { .. })), "{key}: {error:?}");
+    }
+}
+
+#[test]
+fn fractions_use_a_comma_or_a_dot() {
+    let value = json!({ "comma": "PT1,5S", "dot": "PT1.5S", "days": "P0,5D" });
+
+    assert_eq!(Ok(TimeDelta::milliseconds(1_500)), json_nav! { value => "comma"; as duration });
+    assert_eq!(Ok(TimeDelta::milliseconds(1_500)), json_nav! { value => "dot"; as duration });
+    assert_eq!(Ok(TimeDelta::hours(12)), json_nav! { value => "days"; as duration });
+}